@@ -0,0 +1,152 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::models::CacheStats;
+
+struct Entry<V> {
+    value: V,
+    inserted: Instant,
+    last_used: u64,
+}
+
+struct Inner<K, V> {
+    map: HashMap<K, Entry<V>>,
+    tick: u64,
+}
+
+/// Small in-memory cache with a per-entry TTL and LRU eviction once `max_size`
+/// entries are held. Values are cloned out, so keep them cheap or wrap in `Arc`.
+pub struct Cache<K, V> {
+    inner: Mutex<Inner<K, V>>,
+    ttl: Duration,
+    max_size: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Cache<K, V> {
+    pub fn new(ttl: Duration, max_size: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner { map: HashMap::new(), tick: 0 }),
+            ttl,
+            max_size: max_size.max(1),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Fresh value for `key`, if any. Expired entries are dropped on access.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        let expired = match inner.map.get_mut(key) {
+            Some(e) if e.inserted.elapsed() < self.ttl => {
+                e.last_used = tick;
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Some(e.value.clone());
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            inner.map.remove(key);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Drop every entry, e.g. when a new block makes them stale.
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().map.clear();
+    }
+
+    /// Hit/miss counters since startup, plus the current entry count.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.inner.lock().unwrap().map.len(),
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        if !inner.map.contains_key(&key) && inner.map.len() >= self.max_size {
+            let ttl = self.ttl;
+            inner.map.retain(|_, e| e.inserted.elapsed() < ttl);
+            if inner.map.len() >= self.max_size {
+                let lru = inner
+                    .map
+                    .iter()
+                    .min_by_key(|(_, e)| e.last_used)
+                    .map(|(k, _)| k.clone());
+                if let Some(k) = lru {
+                    inner.map.remove(&k);
+                }
+            }
+        }
+
+        inner.map.insert(key, Entry { value, inserted: Instant::now(), last_used: tick });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_expire_after_ttl() {
+        let cache = Cache::new(Duration::from_millis(20), 4);
+        cache.insert("a", 1);
+        assert_eq!(cache.get(&"a"), Some(1));
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_at_max_size() {
+        let cache = Cache::new(Duration::from_secs(60), 2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.get(&"a"); // "b" is now the least recently used
+        cache.insert("c", 3);
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"c"), Some(3));
+    }
+
+    #[test]
+    fn reinserting_a_key_replaces_without_evicting() {
+        let cache = Cache::new(Duration::from_secs(60), 2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("a", 10);
+        assert_eq!(cache.get(&"a"), Some(10));
+        assert_eq!(cache.get(&"b"), Some(2));
+        assert_eq!(cache.stats().entries, 2);
+    }
+
+    #[test]
+    fn counts_hits_and_misses() {
+        let cache = Cache::new(Duration::from_secs(60), 2);
+        cache.get(&"a");
+        cache.insert("a", 1);
+        cache.get(&"a");
+        cache.get(&"a");
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+    }
+}
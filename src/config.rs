@@ -0,0 +1,353 @@
+use anyhow::{Context, Result};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    str::FromStr,
+};
+
+/// Runtime settings, read once at startup from env vars and, optionally, a
+/// TOML file (`BITVIA_CONFIG`, else `./bitvia.toml` if present). File keys are
+/// the env var names in lowercase (`rpc_url = "..."`); env vars win.
+pub struct Config {
+    pub rpc_url: String,
+    pub rpc_user: String,
+    pub rpc_pass: String,
+    // RPC_TIMEOUT_SECS applies to every call; RPC_METHOD_TIMEOUTS
+    // ("getblock:60,getrawtransaction:30") overrides it per method.
+    pub rpc_timeout_secs: u64,
+    pub rpc_method_timeouts: HashMap<String, u64>,
+    pub bind_addr: SocketAddr,
+    pub log_json: bool, // LOG_FORMAT=json, otherwise human-readable
+    pub electrs_addr: String,
+    pub static_cache_secs: u64,
+    // Drop connections whose client accepts no response bytes for this long.
+    pub send_timeout_secs: u64,
+
+    // Prevout resolution for /api/tx: each resolved input is one Electrum round trip,
+    // so raising these increases per-request Electrum load.
+    pub tx_default_resolve: usize,
+    pub tx_max_resolve: usize,
+
+    // Page sizes for paginated endpoints, `<PREFIX>_PAGE_DEFAULT` / `<PREFIX>_PAGE_MAX`.
+    pub block_txs_page: PageLimits, // BLOCK_TXS: txids in /api/block/{hash}
+    pub addr_txs_page: PageLimits,  // ADDR_TXS: resolved txs in /api/addr/{address}/txs
+    pub addr_txs_max_resolve: usize, // prevouts resolved across one such page
+
+    // Background mempool sampler: tick interval, how much history the ring
+    // buffer keeps, and how many of the newest entries each snapshot holds.
+    // The newest entries need the heavy verbose getrawmempool, so they are
+    // refreshed only every `mempool_recent_secs`.
+    pub mempool_sample_secs: u64,
+    pub mempool_history_minutes: u64,
+    pub mempool_recent_txs: usize,
+    pub mempool_recent_secs: u64,
+
+    // Optional bitcoind ZMQ endpoints (`-zmqpubhashblock` / `-zmqpubhashtx`,
+    // e.g. tcp://127.0.0.1:28332). hashblock drives the tip watcher; hashtx
+    // feeds a ring of the newest `zmq_recent_txs` txids.
+    pub zmq_hashblock_addr: Option<String>,
+    pub zmq_hashtx_addr: Option<String>,
+    pub zmq_recent_txs: usize,
+
+    // How often to poll for a new tip when no hashblock feed is connected;
+    // 0 turns polling off and leaves tip-derived caches to their TTLs.
+    pub tip_poll_secs: u64,
+
+    // getpeerinfo reveals the node's peer topology; off unless explicitly enabled.
+    pub expose_peers: bool,
+
+    // Optional JSON map of address -> label (exchanges, burn addresses, ...).
+    pub address_labels_file: Option<String>,
+
+    // Bound on concurrent Electrum operations across all requests; callers
+    // queue for up to `electrs_queue_timeout_secs` before getting a 503.
+    pub electrs_max_concurrency: usize,
+    pub electrs_queue_timeout_secs: u64,
+
+    // Electrum circuit breaker: open after `threshold` connect failures within
+    // `window`, then fail fast for `cooldown`.
+    pub electrs_breaker_threshold: u32,
+    pub electrs_breaker_window_secs: u64,
+    pub electrs_breaker_cooldown_secs: u64,
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        Self::from_source(&Source::load()?)
+    }
+
+    fn from_source(src: &Source) -> Result<Self> {
+        let tx_max_resolve: usize = src.get_or("TX_MAX_RESOLVE", 100)?;
+        let cfg = Self {
+            rpc_url: src.required("RPC_URL")?,
+            rpc_user: src.required("RPC_USER")?,
+            rpc_pass: src.required("RPC_PASS")?,
+            rpc_timeout_secs: src.get_or("RPC_TIMEOUT_SECS", 30)?,
+            rpc_method_timeouts: match src.var("RPC_METHOD_TIMEOUTS") {
+                Some(v) => parse_method_timeouts(&v)?,
+                None => HashMap::new(),
+            },
+            bind_addr: src
+                .var("BIND_ADDR")
+                .unwrap_or_else(|| "0.0.0.0:8000".to_string())
+                .parse()
+                .context("BIND_ADDR must be host:port")?,
+            log_json: src.var("LOG_FORMAT").is_some_and(|v| v.eq_ignore_ascii_case("json")),
+            electrs_addr: src.var("ELECTRS_ADDR").unwrap_or_else(|| "127.0.0.1:50001".to_string()),
+            static_cache_secs: src.get_or("STATIC_CACHE_SECS", 3600)?,
+            send_timeout_secs: src.get_or("SEND_TIMEOUT_SECS", 30)?,
+            tx_default_resolve: src.get_or("TX_DEFAULT_RESOLVE", 20)?.min(tx_max_resolve),
+            tx_max_resolve,
+            block_txs_page: PageLimits::load(src, "BLOCK_TXS", 20, 200)?,
+            addr_txs_page: PageLimits::load(src, "ADDR_TXS", 5, 10)?,
+            addr_txs_max_resolve: src.get_or("ADDR_TXS_MAX_RESOLVE", 100)?,
+            mempool_sample_secs: src.get_or("MEMPOOL_SAMPLE_SECS", 30)?,
+            mempool_history_minutes: src.get_or("MEMPOOL_HISTORY_MINUTES", 24 * 60)?,
+            mempool_recent_txs: src.get_or("MEMPOOL_RECENT_TXS", 25)?,
+            mempool_recent_secs: src.get_or("MEMPOOL_RECENT_SECS", 300)?,
+            zmq_hashblock_addr: src.var("ZMQ_HASHBLOCK_ADDR"),
+            zmq_hashtx_addr: src.var("ZMQ_HASHTX_ADDR"),
+            zmq_recent_txs: src.get_or("ZMQ_RECENT_TXS", 100)?,
+            tip_poll_secs: src.get_or("TIP_POLL_SECS", 5)?,
+            expose_peers: src.flag("EXPOSE_PEERS", false)?,
+            address_labels_file: src.var("ADDRESS_LABELS_FILE"),
+            electrs_max_concurrency: src.get_or("ELECTRS_MAX_CONCURRENCY", 8)?,
+            electrs_queue_timeout_secs: src.get_or("ELECTRS_QUEUE_TIMEOUT_SECS", 30)?,
+            electrs_breaker_threshold: src.get_or("ELECTRS_BREAKER_THRESHOLD", 5)?,
+            electrs_breaker_window_secs: src.get_or("ELECTRS_BREAKER_WINDOW_SECS", 30)?,
+            electrs_breaker_cooldown_secs: src.get_or("ELECTRS_BREAKER_COOLDOWN_SECS", 30)?,
+        };
+        src.reject_unknown()?;
+        Ok(cfg)
+    }
+}
+
+/// Env vars layered over the optional config file.
+struct Source {
+    path: Option<String>,
+    env: HashMap<String, String>,
+    file: HashMap<String, String>, // ENV_NAME -> raw value
+    seen: RefCell<HashSet<String>>,
+}
+
+impl Source {
+    fn load() -> Result<Self> {
+        let path = match std::env::var("BITVIA_CONFIG") {
+            Ok(p) => Some(p),
+            Err(_) => Some("bitvia.toml".to_string()).filter(|p| std::path::Path::new(p).exists()),
+        };
+        let file = match &path {
+            Some(p) => {
+                let raw = std::fs::read_to_string(p).with_context(|| format!("reading config file {p}"))?;
+                parse_file(&raw).with_context(|| format!("parsing config file {p}"))?
+            }
+            None => HashMap::new(),
+        };
+        Ok(Self::new(path, std::env::vars().collect(), file))
+    }
+
+    fn new(path: Option<String>, env: HashMap<String, String>, file: HashMap<String, String>) -> Self {
+        Self { path, env, file, seen: RefCell::new(HashSet::new()) }
+    }
+
+    /// Raw value for `key`: the env var if set, else the file entry.
+    fn var(&self, key: &str) -> Option<String> {
+        self.seen.borrow_mut().insert(key.to_string());
+        self.env.get(key).or_else(|| self.file.get(key)).cloned()
+    }
+
+    fn required(&self, key: &str) -> Result<String> {
+        self.var(key).with_context(|| {
+            format!("missing {key} (set it in the environment or as {} in the config file)", key.to_lowercase())
+        })
+    }
+
+    /// Parse an optional setting, falling back to `default` when unset.
+    fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T> {
+        match self.var(key) {
+            Some(v) => v.parse().map_err(|_| anyhow::anyhow!("{key} has an invalid value: {v}")),
+            None => Ok(default),
+        }
+    }
+
+    /// On/off setting: accepts true/false, 1/0, yes/no and on/off.
+    fn flag(&self, key: &str, default: bool) -> Result<bool> {
+        match self.var(key) {
+            None => Ok(default),
+            Some(v) => parse_flag(&v).ok_or_else(|| anyhow::anyhow!("{key} must be true/false or 1/0, got {v}")),
+        }
+    }
+
+    /// Typos in the file would otherwise be silently ignored.
+    fn reject_unknown(&self) -> Result<()> {
+        let seen = self.seen.borrow();
+        let mut unknown: Vec<String> = self
+            .file
+            .keys()
+            .filter(|k| !seen.contains(*k))
+            .map(|k| k.to_lowercase())
+            .collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        unknown.sort();
+        anyhow::bail!(
+            "unknown keys in config file {}: {}",
+            self.path.as_deref().unwrap_or_default(),
+            unknown.join(", ")
+        )
+    }
+}
+
+/// Flatten the TOML document into env-style `KEY -> value` strings so file and
+/// env values go through the same parsing. `rpc_method_timeouts` may also be
+/// given as a `method = secs` table.
+fn parse_file(raw: &str) -> Result<HashMap<String, String>> {
+    let table: toml::Table = raw.parse()?;
+    table
+        .into_iter()
+        .map(|(key, value)| {
+            let raw = match value {
+                toml::Value::String(s) => s,
+                toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => value.to_string(),
+                toml::Value::Table(t) => t
+                    .iter()
+                    .map(|(k, v)| format!("{k}:{v}"))
+                    .collect::<Vec<_>>()
+                    .join(","),
+                other => anyhow::bail!("{key} has an unsupported value: {other}"),
+            };
+            Ok((key.to_uppercase(), raw))
+        })
+        .collect()
+}
+
+/// Default and maximum `limit` for one paginated endpoint.
+#[derive(Clone, Copy)]
+pub struct PageLimits {
+    pub default: usize,
+    pub max: usize,
+}
+
+impl PageLimits {
+    fn load(src: &Source, prefix: &str, default: usize, max: usize) -> Result<Self> {
+        let max: usize = src.get_or(&format!("{prefix}_PAGE_MAX"), max)?;
+        let default: usize = src.get_or(&format!("{prefix}_PAGE_DEFAULT"), default)?;
+        let max = max.max(1);
+        Ok(Self { default: default.clamp(1, max), max })
+    }
+
+    /// Effective page size for a requested `?limit=`.
+    pub fn limit(&self, requested: Option<usize>) -> usize {
+        requested.unwrap_or(self.default).clamp(1, self.max)
+    }
+}
+
+/// true/false, 1/0, yes/no or on/off, in any case.
+pub fn parse_flag(v: &str) -> Option<bool> {
+    match v.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse `method:secs` pairs separated by commas.
+fn parse_method_timeouts(raw: &str) -> Result<HashMap<String, u64>> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|pair| {
+            let (method, secs) = pair
+                .split_once(':')
+                .with_context(|| format!("RPC_METHOD_TIMEOUTS entry {pair} must be method:secs"))?;
+            let secs = secs
+                .trim()
+                .parse()
+                .with_context(|| format!("RPC_METHOD_TIMEOUTS entry {pair} has invalid seconds"))?;
+            Ok((method.trim().to_string(), secs))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(env: &[(&str, &str)], file: &str) -> Source {
+        let env = env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Source::new(Some("test.toml".to_string()), env, parse_file(file).unwrap())
+    }
+
+    const REQUIRED: &str = "rpc_url = \"http://file\"\nrpc_user = \"u\"\nrpc_pass = \"p\"\n";
+
+    #[test]
+    fn env_overrides_file() {
+        let src = source(&[("RPC_URL", "http://env")], &format!("{REQUIRED}rpc_timeout_secs = 5\n"));
+        let cfg = Config::from_source(&src).unwrap();
+        assert_eq!(cfg.rpc_url, "http://env");
+        assert_eq!(cfg.rpc_user, "u");
+        assert_eq!(cfg.rpc_timeout_secs, 5);
+    }
+
+    #[test]
+    fn method_timeouts_table_form() {
+        let src = source(&[], &format!("{REQUIRED}rpc_method_timeouts = {{ getblock = 60, getrawtransaction = 10 }}\n"));
+        let cfg = Config::from_source(&src).unwrap();
+        assert_eq!(cfg.rpc_method_timeouts.get("getblock"), Some(&60));
+        assert_eq!(cfg.rpc_method_timeouts.get("getrawtransaction"), Some(&10));
+    }
+
+    #[test]
+    fn rejects_unknown_file_keys() {
+        let src = source(&[], &format!("{REQUIRED}expose_peer = true\n"));
+        let err = Config::from_source(&src).err().unwrap().to_string();
+        assert!(err.contains("expose_peer"), "{err}");
+    }
+
+    #[test]
+    fn flags_accept_numeric_forms() {
+        let src = source(&[("EXPOSE_PEERS", "1")], REQUIRED);
+        assert!(Config::from_source(&src).unwrap().expose_peers);
+        let src = source(&[("EXPOSE_PEERS", "0")], REQUIRED);
+        assert!(!Config::from_source(&src).unwrap().expose_peers);
+        let src = source(&[("EXPOSE_PEERS", "maybe")], REQUIRED);
+        assert!(Config::from_source(&src).is_err());
+    }
+
+    #[test]
+    fn query_flags_use_the_same_spellings() {
+        let verify = |uri: &str| {
+            axum::extract::Query::<crate::models::ResolveQ>::try_from_uri(&uri.parse().unwrap())
+                .map(|q| q.0.verify_inputs)
+        };
+        assert_eq!(verify("/tx?verify_inputs=false").unwrap(), Some(false));
+        assert_eq!(verify("/tx?verify_inputs=1").unwrap(), Some(true));
+        assert_eq!(verify("/tx?resolve=5").unwrap(), None);
+        assert!(verify("/tx?verify_inputs=maybe").is_err());
+    }
+
+    #[test]
+    fn page_limits_clamp() {
+        let src = source(&[("X_PAGE_DEFAULT", "50"), ("X_PAGE_MAX", "10")], "");
+        let page = PageLimits::load(&src, "X", 5, 20).unwrap();
+        assert_eq!((page.default, page.max), (10, 10));
+        assert_eq!(page.limit(None), 10);
+        assert_eq!(page.limit(Some(0)), 1);
+        assert_eq!(page.limit(Some(1000)), 10);
+
+        let src = source(&[("X_PAGE_MAX", "0")], "");
+        let page = PageLimits::load(&src, "X", 5, 20).unwrap();
+        assert_eq!((page.default, page.max), (1, 1));
+    }
+
+    #[test]
+    fn method_timeouts_parse_errors() {
+        let ok = parse_method_timeouts(" getblock : 60 ,, getrawtransaction:30").unwrap();
+        assert_eq!(ok.get("getblock"), Some(&60));
+        assert_eq!(ok.len(), 2);
+        assert!(parse_method_timeouts("getblock").is_err());
+        assert!(parse_method_timeouts("getblock:soon").is_err());
+    }
+}
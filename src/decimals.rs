@@ -0,0 +1,63 @@
+use axum::{
+    body::Body,
+    extract::{Query, Request},
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::utils::buffer_json_body;
+
+#[derive(Deserialize)]
+struct DecimalsQ {
+    decimals: Option<u32>,
+}
+
+/// `?decimals=N` (max 8): rewrite BTC amounts in JSON responses as strings
+/// with N decimal places. Without the param responses are left untouched.
+pub async fn format_btc(req: Request, next: Next) -> Response {
+    let decimals = Query::<DecimalsQ>::try_from_uri(req.uri())
+        .ok()
+        .and_then(|q| q.0.decimals);
+    let res = next.run(req).await;
+
+    let Some(decimals) = decimals else { return res };
+    if !res.status().is_success() {
+        return res;
+    }
+    let (parts, mut json) = match buffer_json_body(res).await {
+        Ok(buffered) => buffered,
+        Err(res) => return res,
+    };
+
+    format_amounts(&mut json, decimals.min(8) as usize);
+    Response::from_parts(parts, Body::from(json.to_string()))
+}
+
+/// BTC-denominated fields: our `*_btc` fields plus `value` on Core vout
+/// objects (recognised by their `n` and `scriptPubKey` keys). Any other
+/// `value` is left alone.
+fn is_btc_key(key: &str, in_vout: bool) -> bool {
+    key.ends_with("_btc") || (in_vout && key == "value")
+}
+
+fn format_amounts(v: &mut Value, decimals: usize) {
+    match v {
+        Value::Object(map) => {
+            let in_vout = map.contains_key("n") && map.contains_key("scriptPubKey");
+            for (k, field) in map.iter_mut() {
+                match field {
+                    Value::Number(n) if is_btc_key(k, in_vout) => {
+                        if let Some(f) = n.as_f64() {
+                            *field = Value::String(format!("{f:.decimals$}"));
+                        }
+                    }
+                    _ => format_amounts(field, decimals),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|i| format_amounts(i, decimals)),
+        _ => {}
+    }
+}
@@ -0,0 +1,85 @@
+/// Blocks per difficulty adjustment period.
+pub const EPOCH_LEN: u64 = 2016;
+/// Target block interval in seconds.
+pub const TARGET_SPACING: f64 = 600.0;
+
+/// Progress through the current difficulty epoch and the projected retarget.
+pub struct RetargetEstimate {
+    pub blocks_into_epoch: u64,
+    pub blocks_to_next_adjust: u64,
+    pub avg_block_interval_sec: f64,
+    pub est_diff_change_pct: f64, // clamped to ±50%
+}
+
+/// Height of the first block of the epoch containing `tip_height`.
+pub fn epoch_start_height(tip_height: u64) -> u64 {
+    tip_height - tip_height % EPOCH_LEN
+}
+
+/// Project the next retarget from the blocks mined so far this epoch.
+/// `epoch_start_time` is ignored when the tip opens a new epoch.
+pub fn estimate_retarget(tip_height: u64, tip_time: u64, epoch_start_time: u64) -> RetargetEstimate {
+    let blocks_into_epoch = tip_height % EPOCH_LEN;
+    let blocks_to_next_adjust = EPOCH_LEN - blocks_into_epoch;
+
+    let (avg_block_interval_sec, est_diff_change_pct) = if blocks_into_epoch == 0 {
+        (TARGET_SPACING, 0.0)
+    } else {
+        let dt = tip_time.saturating_sub(epoch_start_time) as f64;
+        let avg = if dt > 0.0 { dt / blocks_into_epoch as f64 } else { TARGET_SPACING };
+        let ratio = TARGET_SPACING / avg;
+        (avg, ((ratio - 1.0) * 100.0).clamp(-50.0, 50.0))
+    };
+
+    RetargetEstimate {
+        blocks_into_epoch,
+        blocks_to_next_adjust,
+        avg_block_interval_sec,
+        est_diff_change_pct,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epoch_boundary_has_no_estimate() {
+        let est = estimate_retarget(2016 * 400, 1_700_000_000, 0);
+        assert_eq!(est.blocks_into_epoch, 0);
+        assert_eq!(est.blocks_to_next_adjust, 2016);
+        assert_eq!(est.avg_block_interval_sec, 600.0);
+        assert_eq!(est.est_diff_change_pct, 0.0);
+    }
+
+    #[test]
+    fn faster_epoch_raises_difficulty() {
+        // 1008 blocks at 500s each
+        let est = estimate_retarget(2016 * 400 + 1008, 1_000_000 + 1008 * 500, 1_000_000);
+        assert_eq!(est.blocks_to_next_adjust, 1008);
+        assert_eq!(est.avg_block_interval_sec, 500.0);
+        assert!((est.est_diff_change_pct - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn slower_epoch_lowers_difficulty() {
+        // 100 blocks at 750s each
+        let est = estimate_retarget(2016 * 400 + 100, 1_000_000 + 100 * 750, 1_000_000);
+        assert_eq!(est.avg_block_interval_sec, 750.0);
+        assert!((est.est_diff_change_pct + 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn change_is_clamped() {
+        let fast = estimate_retarget(2016 + 10, 1_000_000 + 10 * 60, 1_000_000);
+        assert_eq!(fast.est_diff_change_pct, 50.0);
+        let slow = estimate_retarget(2016 + 10, 1_000_000 + 10 * 6000, 1_000_000);
+        assert_eq!(slow.est_diff_change_pct, -50.0);
+    }
+
+    #[test]
+    fn start_height_of_epoch() {
+        assert_eq!(epoch_start_height(2016 * 3 + 5), 2016 * 3);
+        assert_eq!(epoch_start_height(2016 * 3), 2016 * 3);
+    }
+}
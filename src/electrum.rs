@@ -0,0 +1,376 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use axum::http::StatusCode;
+use bitcoin::{Address, Network, Script, Transaction, Txid};
+use electrum_client::{Client as ElectrumClient, ConfigBuilder, ElectrumApi, GetHistoryRes};
+use tokio::sync::OwnedSemaphorePermit;
+
+use crate::state::AppState;
+
+/// Consecutive-failure circuit breaker for Electrum connections.
+pub struct CircuitBreaker {
+    inner: Mutex<BreakerInner>,
+    threshold: u32,
+    window: Duration,
+    cooldown: Duration,
+}
+
+#[derive(Default)]
+struct BreakerInner {
+    failures: u32,
+    first_failure: Option<Instant>,
+    open_until: Option<Instant>,
+    // when the single half-open probe was handed out
+    probe_since: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, window: Duration, cooldown: Duration) -> Self {
+        Self {
+            inner: Mutex::new(BreakerInner::default()),
+            threshold: threshold.max(1),
+            window,
+            cooldown,
+        }
+    }
+
+    /// False while open. Once the cooldown has elapsed a single caller is let
+    /// through as a probe; everyone else keeps failing fast until it reports
+    /// back (or goes missing for a whole cooldown).
+    pub fn allow(&self) -> bool {
+        self.allow_at(Instant::now())
+    }
+
+    fn allow_at(&self, now: Instant) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.open_until {
+            None => true,
+            Some(until) if now < until => false,
+            Some(_) => {
+                let probe_out = inner
+                    .probe_since
+                    .is_some_and(|since| now.duration_since(since) < self.cooldown);
+                if !probe_out {
+                    inner.probe_since = Some(now);
+                }
+                !probe_out
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        *self.inner.lock().unwrap() = BreakerInner::default();
+    }
+
+    pub fn record_failure(&self) {
+        self.record_failure_at(Instant::now());
+    }
+
+    fn record_failure_at(&self, now: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.first_failure {
+            Some(first) if now.duration_since(first) <= self.window => inner.failures += 1,
+            _ => {
+                inner.failures = 1;
+                inner.first_failure = Some(now);
+            }
+        }
+        // a failed probe after cooldown re-opens immediately
+        let probing = inner.open_until.is_some();
+        if probing || inner.failures >= self.threshold {
+            inner.open_until = Some(now + self.cooldown);
+            inner.probe_since = None;
+        }
+    }
+
+    /// "closed", "open", or "half-open" (cooldown elapsed, awaiting a probe).
+    pub fn state(&self) -> &'static str {
+        match self.inner.lock().unwrap().open_until {
+            None => "closed",
+            Some(until) if Instant::now() < until => "open",
+            Some(_) => "half-open",
+        }
+    }
+}
+
+/// Reserve an Electrum slot before entering `spawn_blocking`; hold the permit
+/// for the duration of the blocking work. Fails fast while the breaker is open
+/// and gives up with a 503 if no slot frees up within the queue timeout.
+pub async fn permit(st: &AppState) -> Result<OwnedSemaphorePermit, (StatusCode, String)> {
+    let unavailable = || {
+        (StatusCode::SERVICE_UNAVAILABLE, "electrum unavailable, retry shortly".to_string())
+    };
+    if !st.electrum_breaker.allow() {
+        return Err(unavailable());
+    }
+    match tokio::time::timeout(st.electrum_queue_timeout, st.electrum_slots.clone().acquire_owned()).await {
+        Ok(Ok(p)) => Ok(p),
+        _ => Err(unavailable()),
+    }
+}
+
+/// Pause before the single retry of a transient Electrum failure.
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Electrum connection whose calls are retried once, on a fresh connection,
+/// when they fail with a connection/IO error. Server-side errors such as
+/// "not found" are returned as-is. Blocking; use from `spawn_blocking`.
+pub struct Conn<'a> {
+    st: &'a AppState,
+    cli: ElectrumClient,
+}
+
+/// Connect to Electrs, feeding the outcome into the circuit breaker.
+pub fn connect(st: &AppState) -> anyhow::Result<Conn<'_>> {
+    Ok(Conn { st, cli: open(st)? })
+}
+
+fn open(st: &AppState) -> anyhow::Result<ElectrumClient> {
+    match open_addr(&st.electrs_addr) {
+        Ok(cli) => {
+            st.electrum_breaker.record_success();
+            Ok(cli)
+        }
+        Err(e) => {
+            st.electrum_breaker.record_failure();
+            Err(e.into())
+        }
+    }
+}
+
+/// Plain connection with the client's own (slow, backoff-based) retry disabled.
+fn open_addr(addr: &str) -> Result<ElectrumClient, electrum_client::Error> {
+    let config = ConfigBuilder::new().retry(0).build();
+    ElectrumClient::from_config(&format!("tcp://{addr}"), config)
+}
+
+/// Connection-level failures worth one more try; protocol/decode errors are not.
+fn is_transient(e: &electrum_client::Error) -> bool {
+    use electrum_client::Error as E;
+    matches!(
+        e,
+        E::IOError(_) | E::SharedIOError(_) | E::Mpsc | E::CouldntLockReader | E::AllAttemptsErrored(_)
+    )
+}
+
+/// Whether the server answered that the requested item does not exist, as
+/// opposed to the call failing.
+pub fn is_not_found(e: &anyhow::Error) -> bool {
+    match e.downcast_ref::<electrum_client::Error>() {
+        Some(electrum_client::Error::Protocol(v)) => {
+            let msg = v.to_string().to_lowercase();
+            msg.contains("not found") || msg.contains("no such") || msg.contains("missing")
+        }
+        _ => false,
+    }
+}
+
+/// Run `f`; on a transient error wait briefly, reconnect, and run it once more.
+fn retry_once<T>(
+    cli: &mut ElectrumClient,
+    reconnect: impl FnOnce() -> anyhow::Result<ElectrumClient>,
+    f: impl Fn(&ElectrumClient) -> Result<T, electrum_client::Error>,
+) -> anyhow::Result<T> {
+    match f(cli) {
+        Err(e) if is_transient(&e) => {
+            tracing::debug!(error = %e, "electrum call failed, retrying on a new connection");
+            std::thread::sleep(RETRY_DELAY);
+            *cli = reconnect()?;
+            Ok(f(cli)?)
+        }
+        res => Ok(res?),
+    }
+}
+
+impl Conn<'_> {
+    fn call<T>(&mut self, f: impl Fn(&ElectrumClient) -> Result<T, electrum_client::Error>) -> anyhow::Result<T> {
+        let st = self.st;
+        retry_once(&mut self.cli, || open(st), f)
+    }
+
+    /// `server.ping`, the cheapest round trip the protocol offers.
+    pub fn ping(&mut self) -> anyhow::Result<()> {
+        self.call(|c| c.ping())
+    }
+
+    pub fn transaction_get(&mut self, txid: &Txid) -> anyhow::Result<Transaction> {
+        self.call(|c| c.transaction_get(txid))
+    }
+
+    pub fn script_get_history(&mut self, script: &Script) -> anyhow::Result<Vec<GetHistoryRes>> {
+        self.call(|c| c.script_get_history(script))
+    }
+
+    /// `mempool.get_fee_histogram`: `(feerate sat/vB, vsize)` buckets, highest fee first.
+    pub fn fee_histogram(&mut self) -> anyhow::Result<Vec<(f64, u64)>> {
+        let raw = self.call(|c| c.raw_call("mempool.get_fee_histogram", []))?;
+        Ok(serde_json::from_value(raw)?)
+    }
+}
+
+/// Render a scriptPubKey as a mainnet address, or "(no address)".
+pub fn script_address(script: &Script) -> String {
+    Address::from_script(script, Network::Bitcoin)
+        .map(|a| a.to_string())
+        .unwrap_or_else(|_| "(no address)".to_string())
+}
+
+/// Value (sats) and address of output `vout` of `txid`.
+pub fn fetch_prevout(cli: &mut Conn, txid: &Txid, vout: u32) -> anyhow::Result<(u64, String)> {
+    let prev = cli.transaction_get(txid)?;
+    let out = prev
+        .output
+        .get(vout as usize)
+        .ok_or_else(|| anyhow::anyhow!("prevout index {} out of range", vout))?;
+    Ok((out.value.to_sat(), script_address(&out.script_pubkey)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        str::FromStr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    // version 2, one input spending a null outpoint, one empty output, locktime 0
+    const RAW_TX: &str = "02000000010000000000000000000000000000000000000000000000000000000000000000ffffffff00ffffffff0100000000000000000000000000";
+
+    /// Mock Electrs: the first `drop_first` connections are closed right after
+    /// reading a request; later ones answer with `reply(id)`.
+    fn mock_server(drop_first: usize, reply: fn(u64) -> String) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let conns = Arc::new(AtomicUsize::new(0));
+        let seen = conns.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let n = seen.fetch_add(1, Ordering::SeqCst);
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap_or(0) > 0 {
+                    if n < drop_first {
+                        break;
+                    }
+                    let req: serde_json::Value = serde_json::from_str(&line).unwrap();
+                    let id = req["id"].as_u64().unwrap();
+                    stream.write_all(format!("{}\n", reply(id)).as_bytes()).unwrap();
+                    line.clear();
+                }
+            }
+        });
+        (addr, conns)
+    }
+
+    fn get_tx(addr: &str) -> anyhow::Result<Transaction> {
+        let txid = Txid::from_str("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let mut cli = open_addr(addr)?;
+        retry_once(&mut cli, || Ok(open_addr(addr)?), |c| c.transaction_get(&txid))
+    }
+
+    const SEC: Duration = Duration::from_secs(1);
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(3, 10 * SEC, 30 * SEC)
+    }
+
+    #[test]
+    fn breaker_opens_at_threshold() {
+        let b = breaker();
+        let t0 = Instant::now();
+        b.record_failure_at(t0);
+        b.record_failure_at(t0 + SEC);
+        assert!(b.allow_at(t0 + SEC));
+        b.record_failure_at(t0 + 2 * SEC);
+        assert!(!b.allow_at(t0 + 3 * SEC));
+        assert_eq!(b.state(), "open");
+    }
+
+    #[test]
+    fn breaker_window_expiry_resets_count() {
+        let b = breaker();
+        let t0 = Instant::now();
+        b.record_failure_at(t0);
+        b.record_failure_at(t0 + SEC);
+        // the third failure lands outside the window and starts a new count
+        b.record_failure_at(t0 + 11 * SEC);
+        assert!(b.allow_at(t0 + 12 * SEC));
+        assert_eq!(b.state(), "closed");
+    }
+
+    #[test]
+    fn breaker_hands_out_one_probe() {
+        let b = breaker();
+        let t0 = Instant::now();
+        (0..3).for_each(|_| b.record_failure_at(t0));
+        let after = t0 + 31 * SEC;
+        assert!(b.allow_at(after));
+        assert!(!b.allow_at(after));
+        assert!(!b.allow_at(after + SEC));
+        // a probe that never reports back is replaced after a cooldown
+        assert!(b.allow_at(after + 30 * SEC));
+    }
+
+    #[test]
+    fn breaker_failed_probe_reopens() {
+        let b = breaker();
+        let t0 = Instant::now();
+        (0..3).for_each(|_| b.record_failure_at(t0));
+        let after = t0 + 31 * SEC;
+        assert!(b.allow_at(after));
+        b.record_failure_at(after);
+        assert!(!b.allow_at(after + SEC));
+        assert!(!b.allow_at(after + 29 * SEC));
+        assert!(b.allow_at(after + 30 * SEC));
+    }
+
+    #[test]
+    fn breaker_successful_probe_closes() {
+        let b = breaker();
+        let t0 = Instant::now();
+        (0..3).for_each(|_| b.record_failure_at(t0));
+        let after = t0 + 31 * SEC;
+        assert!(b.allow_at(after));
+        b.record_success();
+        assert!(b.allow_at(after));
+        assert!(b.allow_at(after));
+        assert_eq!(b.state(), "closed");
+    }
+
+    #[test]
+    fn retries_dropped_connection_once() {
+        let (addr, conns) = mock_server(1, |id| {
+            format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"{RAW_TX}"}}"#)
+        });
+        let tx = get_tx(&addr).unwrap();
+        assert_eq!(tx.output.len(), 1);
+        assert_eq!(conns.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn gives_up_after_second_failure() {
+        let (addr, conns) = mock_server(usize::MAX, |_| unreachable!());
+        let err = get_tx(&addr).unwrap_err();
+        assert!(!is_not_found(&err));
+        assert_eq!(conns.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn does_not_retry_server_errors() {
+        let (addr, conns) = mock_server(0, |id| {
+            format!(r#"{{"jsonrpc":"2.0","id":{id},"error":{{"code":2,"message":"missing transaction"}}}}"#)
+        });
+        let err = get_tx(&addr).unwrap_err();
+        assert!(is_not_found(&err));
+        assert_eq!(conns.load(Ordering::SeqCst), 1);
+    }
+}
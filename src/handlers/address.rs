@@ -0,0 +1,209 @@
+use std::{collections::HashSet, str::FromStr, sync::Arc};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use bitcoin::{Address, Network, ScriptBuf, Txid};
+use electrum_client::GetHistoryRes;
+
+use crate::{
+    electrum,
+    models::{
+        AddrMempool, AddrMempoolTx, AddrTx, AddrTxOutput, AddrTxsQ, AddrTxsView, AddrUsed,
+        PrevoutResolved,
+    },
+    state::AppState,
+    utils::internalize,
+};
+
+/// Parse a mainnet address into its scriptPubKey (400 on anything else).
+fn address_script(address: &str) -> Result<ScriptBuf, (StatusCode, String)> {
+    Address::from_str(address)
+        .and_then(|a| a.require_network(Network::Bitcoin))
+        .map(|a| a.script_pubkey())
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid address {address}: {e}")))
+}
+
+/// Electrum history for a script, newest first (mempool entries lead).
+fn history_newest_first(cli: &mut electrum::Conn, script: &ScriptBuf) -> anyhow::Result<Vec<GetHistoryRes>> {
+    let mut hist = cli.script_get_history(script)?;
+    // electrs lists confirmed by height ascending, then mempool (height <= 0)
+    hist.sort_by_key(|h| if h.height <= 0 { i32::MAX } else { h.height });
+    hist.reverse();
+    Ok(hist)
+}
+
+/// Whether an address has any history, from the history length alone.
+pub async fn addr_used(
+    State(st): State<Arc<AppState>>,
+    Path(address): Path<String>,
+) -> Result<Json<AddrUsed>, (StatusCode, String)> {
+    let script = address_script(&address)?;
+
+    let permit = electrum::permit(&st).await?;
+    let st_blocking = st.clone();
+    let tx_count = tokio::task::spawn_blocking(move || -> anyhow::Result<usize> {
+        let _permit = permit;
+        let mut cli = electrum::connect(&st_blocking)?;
+        Ok(cli.script_get_history(&script)?.len())
+    })
+    .await
+    .map_err(|e| internalize(format!("electrum task failed: {e}")))?
+    .map_err(|e| {
+        tracing::warn!(error = %e, "electrum address history failed");
+        internalize(e)
+    })?;
+
+    Ok(Json(AddrUsed { address, used: tx_count > 0, tx_count }))
+}
+
+/// Most unconfirmed entries we resolve for `/mempool`.
+const MAX_MEMPOOL_TXS: usize = 50;
+
+/// Unconfirmed history only, for payment detection: each entry carries the
+/// net value change for the address (outputs to it minus inputs from it).
+pub async fn addr_mempool(
+    State(st): State<Arc<AppState>>,
+    Path(address): Path<String>,
+) -> Result<Json<AddrMempool>, (StatusCode, String)> {
+    let script = address_script(&address)?;
+
+    let permit = electrum::permit(&st).await?;
+    let st_blocking = st.clone();
+    let (txs, more_txs) = tokio::task::spawn_blocking(move || -> anyhow::Result<(Vec<AddrMempoolTx>, bool)> {
+        let _permit = permit;
+        let mut cli = electrum::connect(&st_blocking)?;
+
+        let hist = history_newest_first(&mut cli, &script)?;
+        // only inputs spending one of the address's own txs can debit it
+        let own_txids: HashSet<Txid> = hist.iter().map(|h| h.tx_hash).collect();
+        let pending: Vec<GetHistoryRes> = hist.into_iter().filter(|h| h.height <= 0).collect();
+
+        let mut txs = Vec::new();
+        for h in pending.iter().take(MAX_MEMPOOL_TXS) {
+            let tx = cli.transaction_get(&h.tx_hash)?;
+
+            let received: u64 = tx
+                .output
+                .iter()
+                .filter(|o| o.script_pubkey == script)
+                .map(|o| o.value.to_sat())
+                .sum();
+            let mut spent: u64 = 0;
+            for vin in tx.input.iter().filter(|i| own_txids.contains(&i.previous_output.txid)) {
+                let prev = cli.transaction_get(&vin.previous_output.txid)?;
+                if let Some(o) = prev.output.get(vin.previous_output.vout as usize)
+                    && o.script_pubkey == script
+                {
+                    spent += o.value.to_sat();
+                }
+            }
+
+            let delta_sats = received as i64 - spent as i64;
+            txs.push(AddrMempoolTx {
+                txid: h.tx_hash.to_string(),
+                delta_sats,
+                delta_btc: delta_sats as f64 / 100_000_000.0,
+                fee_sats: h.fee,
+            });
+        }
+        Ok((txs, pending.len() > MAX_MEMPOOL_TXS))
+    })
+    .await
+    .map_err(|e| internalize(format!("electrum task failed: {e}")))?
+    .map_err(|e| {
+        tracing::warn!(error = %e, "electrum address mempool failed");
+        internalize(e)
+    })?;
+
+    Ok(Json(AddrMempool { address, txs, more_txs }))
+}
+
+/// Full-transaction activity feed. Each item costs one Electrum fetch per
+/// resolved input, so pages are kept small and inputs are capped both per
+/// transaction (`?resolve=`) and across the page (`ADDR_TXS_MAX_RESOLVE`).
+pub async fn addr_txs(
+    State(st): State<Arc<AppState>>,
+    Path(address): Path<String>,
+    Query(q): Query<AddrTxsQ>,
+) -> Result<Json<AddrTxsView>, (StatusCode, String)> {
+    let script = address_script(&address)?;
+    let limit = st.addr_txs_page.limit(q.limit);
+    let offset = q.offset.unwrap_or(0);
+    let per_tx = st.resolve_cap(q.resolve);
+
+    let permit = electrum::permit(&st).await?;
+    let st_blocking = st.clone();
+    let (txs, total) = tokio::task::spawn_blocking(move || -> anyhow::Result<(Vec<AddrTx>, usize)> {
+        let _permit = permit;
+        let mut cli = electrum::connect(&st_blocking)?;
+
+        let hist = history_newest_first(&mut cli, &script)?;
+        let total = hist.len();
+
+        let mut budget = st_blocking.addr_txs_max_resolve;
+        let mut txs = Vec::new();
+        for h in hist.iter().skip(offset).take(limit) {
+            let tx = cli.transaction_get(&h.tx_hash)?;
+            let is_coinbase = tx.is_coinbase();
+
+            let resolve_n = if is_coinbase { 0 } else { per_tx.min(budget).min(tx.input.len()) };
+            budget -= resolve_n;
+            let mut inputs = Vec::with_capacity(resolve_n);
+            for vin in tx.input.iter().take(resolve_n) {
+                let prev = vin.previous_output;
+                let (val_sats, addr) = electrum::fetch_prevout(&mut cli, &prev.txid, prev.vout)?;
+                inputs.push(PrevoutResolved {
+                    txid: prev.txid.to_string(),
+                    vout: prev.vout,
+                    value_btc: (val_sats as f64) / 100_000_000.0,
+                    label: st_blocking.label_for(&addr),
+                    address: addr,
+                });
+            }
+
+            let outputs = tx
+                .output
+                .iter()
+                .enumerate()
+                .map(|(n, o)| {
+                    let addr = electrum::script_address(&o.script_pubkey);
+                    AddrTxOutput {
+                        n,
+                        value_btc: (o.value.to_sat() as f64) / 100_000_000.0,
+                        label: st_blocking.label_for(&addr),
+                        address: addr,
+                    }
+                })
+                .collect();
+
+            txs.push(AddrTx {
+                txid: h.tx_hash.to_string(),
+                height: (h.height > 0).then_some(h.height as u64),
+                confirmed: h.height > 0,
+                is_coinbase,
+                inputs,
+                more_inputs: !is_coinbase && resolve_n < tx.input.len(),
+                outputs,
+            });
+        }
+        Ok((txs, total))
+    })
+    .await
+    .map_err(|e| internalize(format!("electrum task failed: {e}")))?
+    .map_err(|e| {
+        tracing::warn!(error = %e, "electrum address history failed");
+        internalize(e)
+    })?;
+
+    Ok(Json(AddrTxsView {
+        address,
+        more_txs: offset + txs.len() < total,
+        txs,
+        total_txs: total,
+        offset,
+        limit,
+    }))
+}
@@ -8,27 +8,71 @@ use axum::{
 use serde_json::json;
 
 use crate::{
-    models::{BlockHashResp, BlockPageQ, BlockView, GetBlockV1},
+    models::{
+        BlockHashResp, BlockHeaderLite, BlockPageQ, BlockTip, BlockView, ChainInfo,
+        CoinbaseOutput, CoinbaseView, GetBlockV1, TxDecoded,
+    },
     rpc::rpc_call,
     state::AppState,
-    utils::internalize,
+    supply::current_subsidy_btc,
+    utils::{
+        bip34_height, coinbase_tag, internalize, validate_hash, validate_height, vout_value_btc,
+        weight_percent,
+    },
 };
 
 pub async fn blockhash_by_height(
     State(st): State<Arc<AppState>>,
     Path(height): Path<u64>,
 ) -> Result<Json<BlockHashResp>, (StatusCode, String)> {
+    validate_height(height)?;
     let hash: String = rpc_call(&st, "getblockhash", json!([height]))
         .await
-        .map_err(internalize)?;
+        .map_err(|e| {
+            let msg = e.to_string();
+            if msg.to_lowercase().contains("out of range") {
+                (StatusCode::NOT_FOUND, format!("no block at height {height}: {msg}"))
+            } else {
+                internalize(msg)
+            }
+        })?;
     Ok(Json(BlockHashResp { height, hash }))
 }
 
+pub async fn block_tip(
+    State(st): State<Arc<AppState>>,
+) -> Result<Json<BlockTip>, (StatusCode, String)> {
+    current_tip(&st).await.map(Json)
+}
+
+/// Tip height/hash/time, served from the short-lived tip cache when fresh.
+pub async fn current_tip(st: &AppState) -> Result<BlockTip, (StatusCode, String)> {
+    if let Some(tip) = st.tip_cache.get(&()) {
+        return Ok(tip);
+    }
+
+    let ci: ChainInfo = rpc_call(st, "getblockchaininfo", json!([]))
+        .await
+        .map_err(internalize)?;
+    let hdr: BlockHeaderLite = rpc_call(st, "getblockheader", json!([ci.bestblockhash, true]))
+        .await
+        .map_err(internalize)?;
+
+    let tip = BlockTip {
+        height: ci.blocks,
+        hash: ci.bestblockhash,
+        time: hdr.time,
+    };
+    st.tip_cache.insert((), tip.clone());
+    Ok(tip)
+}
+
 pub async fn block_by_hash(
     State(st): State<Arc<AppState>>,
     Path(hash): Path<String>,
     Query(q): Query<BlockPageQ>,
 ) -> Result<Json<BlockView>, (StatusCode, String)> {
+    validate_hash("block hash", &hash)?;
     // v=1 → returns txids (strings), not full tx objects
     let gb: GetBlockV1 = rpc_call(&st, "getblock", json!([hash, 1]))
         .await
@@ -39,7 +83,7 @@ pub async fn block_by_hash(
 
     // paging
     let total = all.len();
-    let limit = q.limit.unwrap_or(20).clamp(1, 200);
+    let limit = st.block_txs_page.limit(q.limit);
     let offset = q.offset.unwrap_or(0).min(total);
     let end = (offset + limit).min(total);
     let txids = if offset < end { all[offset..end].to_vec() } else { Vec::new() };
@@ -51,7 +95,9 @@ pub async fn block_by_hash(
         mediantime: gb.mediantime,
         size: gb.size,
         weight: gb.weight,
+        weight_percent: weight_percent(gb.weight),
         n_tx: gb.n_tx,
+        confirmations: gb.confirmations,
         prev: gb.prevblockhash,
         next: gb.nextblockhash,
         txids,
@@ -63,3 +109,90 @@ pub async fn block_by_hash(
     Ok(Json(out))
 }
 
+/// 404 for unknown block hashes, 502 for anything else.
+fn block_lookup_err(e: anyhow::Error) -> (StatusCode, String) {
+    let msg = e.to_string();
+    if msg.to_lowercase().contains("block not found") {
+        (StatusCode::NOT_FOUND, format!("block not found: {msg}"))
+    } else {
+        internalize(msg)
+    }
+}
+
+/// Serialized 80-byte header as hex, for independent PoW checks.
+pub async fn block_header_hex(
+    State(st): State<Arc<AppState>>,
+    Path(hash): Path<String>,
+) -> Result<String, (StatusCode, String)> {
+    validate_hash("block hash", &hash)?;
+    rpc_call(&st, "getblockheader", json!([hash, false]))
+        .await
+        .map_err(block_lookup_err)
+}
+
+pub async fn block_coinbase(
+    State(st): State<Arc<AppState>>,
+    Path(hash): Path<String>,
+) -> Result<Json<CoinbaseView>, (StatusCode, String)> {
+    validate_hash("block hash", &hash)?;
+    let gb: GetBlockV1 = rpc_call(&st, "getblock", json!([hash, 1]))
+        .await
+        .map_err(block_lookup_err)?;
+    let cb_txid = gb
+        .tx
+        .first()
+        .ok_or_else(|| internalize("block has no transactions"))?;
+
+    // passing the blockhash lets this work without -txindex
+    let tx: TxDecoded = rpc_call(&st, "getrawtransaction", json!([cb_txid, true, gb.hash]))
+        .await
+        .map_err(internalize)?;
+
+    let script_sig_hex = tx
+        .vin
+        .first()
+        .and_then(|v| v.get("coinbase"))
+        .and_then(|c| c.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let script_sig = hex::decode(&script_sig_hex).unwrap_or_default();
+
+    let outputs: Vec<CoinbaseOutput> = tx
+        .vout
+        .iter()
+        .enumerate()
+        .map(|(n, v)| {
+            let spk = v.get("scriptPubKey");
+            let address = spk
+                .and_then(|s| s.get("address"))
+                .and_then(|a| a.as_str())
+                .map(str::to_string);
+            CoinbaseOutput {
+                n,
+                value_btc: vout_value_btc(v),
+                label: address.as_deref().and_then(|a| st.label_for(a)),
+                address,
+                script_type: spk
+                    .and_then(|s| s.get("type"))
+                    .and_then(|t| t.as_str())
+                    .map(str::to_string),
+            }
+        })
+        .collect();
+
+    let total_reward_btc: f64 = outputs.iter().map(|o| o.value_btc).sum();
+    let subsidy_btc = current_subsidy_btc(gb.height);
+
+    Ok(Json(CoinbaseView {
+        txid: tx.txid,
+        block_hash: gb.hash,
+        height: gb.height,
+        bip34_height: bip34_height(&script_sig, gb.height),
+        miner_tag: coinbase_tag(&script_sig),
+        script_sig_hex,
+        outputs,
+        total_reward_btc,
+        subsidy_btc,
+        fees_btc: (total_reward_btc - subsidy_btc).max(0.0),
+    }))
+}
@@ -0,0 +1,34 @@
+use axum::{extract::Query, http::StatusCode, Json};
+
+use crate::{
+    models::{ConvertQ, ConvertResp},
+    utils::{format_sats_btc, parse_btc_sats},
+};
+
+/// BTC <-> sat conversion using integer math. `usd` is accepted as a unit but
+/// there is no price source yet, so it always reports unavailable.
+pub async fn convert(Query(q): Query<ConvertQ>) -> Result<Json<ConvertResp>, (StatusCode, String)> {
+    let from = q.from.to_lowercase();
+    let to = q.to.to_lowercase();
+    for unit in [&from, &to] {
+        match unit.as_str() {
+            "btc" | "sat" => {}
+            "usd" => {
+                return Err((StatusCode::SERVICE_UNAVAILABLE, "no price data available for usd".to_string()));
+            }
+            other => return Err((StatusCode::BAD_REQUEST, format!("unknown unit {other}; use btc, sat or usd"))),
+        }
+    }
+
+    let bad_amount = || (StatusCode::BAD_REQUEST, format!("invalid {from} amount: {}", q.amount));
+    let sats: u64 = match from.as_str() {
+        "sat" => q.amount.trim().parse().map_err(|_| bad_amount())?,
+        _ => parse_btc_sats(&q.amount).ok_or_else(bad_amount)?,
+    };
+    let result = match to.as_str() {
+        "sat" => sats.to_string(),
+        _ => format_sats_btc(sats),
+    };
+
+    Ok(Json(ConvertResp { amount: q.amount, from, to, result }))
+}
@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use crate::{
+    electrum,
+    handlers::{blocks::current_tip, mempool::mempool_info},
+    models::{FeeDepth, FeeDepthQ, FeeEstimates, FeeTarget, FeesQ, RecommendedFees, SmartFeeEstimate},
+    rpc::rpc_call,
+    state::AppState,
+    utils::{internalize, unix_now},
+};
+
+/// Confirmation targets (in blocks) we ask Core about.
+const TARGETS: [u64; 4] = [1, 3, 6, 144];
+
+/// Fee table for targets 1/3/6/144, cached per chain tip for up to 60 s.
+/// `?fresh=1` recomputes it regardless.
+pub async fn fee_estimates(
+    State(st): State<Arc<AppState>>,
+    Query(q): Query<FeesQ>,
+) -> Result<Json<FeeEstimates>, (axum::http::StatusCode, String)> {
+    let tip = current_tip(&st).await?.hash;
+    if q.fresh.unwrap_or(0) == 0
+        && let Some(cached) = st.fee_table_cache.get(&tip)
+    {
+        return Ok(Json(cached));
+    }
+
+    let mut estimates = Vec::with_capacity(TARGETS.len());
+    for target in TARGETS {
+        let est: SmartFeeEstimate = rpc_call(&st, "estimatesmartfee", serde_json::json!([target]))
+            .await
+            .map_err(internalize)?;
+        estimates.push(fee_target(target, est));
+    }
+    let table = FeeEstimates { estimates, computed_at: unix_now() };
+    st.fee_table_cache.insert(tip, table.clone());
+    Ok(Json(table))
+}
+
+/// Named tiers from targets 1/3/6/144 plus `mempoolminfee` as the floor.
+pub async fn recommended(
+    State(st): State<Arc<AppState>>,
+) -> Result<Json<RecommendedFees>, (axum::http::StatusCode, String)> {
+    if let Some(cached) = st.fees_cache.get(&()) {
+        return Ok(Json(cached));
+    }
+
+    let mut rates = [None; 4];
+    for (rate, target) in rates.iter_mut().zip(TARGETS) {
+        let est: SmartFeeEstimate = rpc_call(&st, "estimatesmartfee", serde_json::json!([target]))
+            .await
+            .map_err(internalize)?;
+        *rate = fee_target(target, est).feerate_sat_vb;
+    }
+    let mp = mempool_info(&st).await?;
+    let minimum = mp.mempoolminfee * 100_000_000.0 / 1000.0;
+
+    let fees = recommended_tiers(rates, minimum);
+    st.fees_cache.insert((), fees.clone());
+    Ok(Json(fees))
+}
+
+/// Round to whole sat/vB and force the tiers to be non-increasing and never
+/// below the minimum. A target without an estimate borrows its nearest
+/// neighbour's: the next slower one for `fastest`, the faster one otherwise.
+fn recommended_tiers(rates: [Option<f64>; 4], minimum: f64) -> RecommendedFees {
+    let minimum = (minimum.round() as u64).max(1);
+    let rounded = rates.map(|r| r.map(|r| (r.round() as u64).max(minimum)));
+    let mut tiers = [minimum; 4];
+    tiers[0] = rounded.iter().flatten().next().copied().unwrap_or(minimum);
+    for i in 1..tiers.len() {
+        tiers[i] = rounded[i].unwrap_or(tiers[i - 1]).min(tiers[i - 1]);
+    }
+    let [fastest, half_hour, hour, economy] = tiers;
+    RecommendedFees { fastest, half_hour, hour, economy, minimum }
+}
+
+/// Roughly how much block space one block clears.
+const BLOCK_VSIZE: u64 = 1_000_000;
+/// Don't project further out than about a day of blocks.
+const MAX_DEPTH_BLOCKS: u64 = 144;
+
+/// Blocks until a tx paying `feerate` would confirm if the mempool queued
+/// at or above that rate cleared at ~1M vB per block and nothing new arrived.
+pub async fn depth(
+    State(st): State<Arc<AppState>>,
+    Query(q): Query<FeeDepthQ>,
+) -> Result<Json<FeeDepth>, (StatusCode, String)> {
+    if !(q.feerate.is_finite() && q.feerate >= 0.0) {
+        return Err((StatusCode::BAD_REQUEST, "feerate must be a non-negative number".to_string()));
+    }
+
+    if let Some(histogram) = st.mempool.latest().and_then(|snap| snap.histogram.clone()) {
+        return Ok(Json(depth_for(&histogram, q.feerate)));
+    }
+
+    let permit = electrum::permit(&st).await?;
+    let st_blocking = st.clone();
+    let histogram = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        electrum::connect(&st_blocking)?.fee_histogram()
+    })
+    .await
+    .map_err(|e| internalize(format!("electrum task failed: {e}")))?
+    .map_err(|e| {
+        tracing::warn!(error = %e, "electrum fee histogram failed");
+        internalize(e)
+    })?;
+
+    Ok(Json(depth_for(&histogram, q.feerate)))
+}
+
+fn depth_for(histogram: &[(f64, u64)], feerate: f64) -> FeeDepth {
+    let vsize_ahead: u64 = histogram
+        .iter()
+        .filter(|(rate, _)| *rate >= feerate)
+        .map(|(_, vsize)| vsize)
+        .sum();
+    let blocks = vsize_ahead / BLOCK_VSIZE + 1;
+    FeeDepth {
+        feerate_sat_vb: feerate,
+        vsize_ahead,
+        est_blocks: blocks.min(MAX_DEPTH_BLOCKS),
+        capped: blocks > MAX_DEPTH_BLOCKS,
+    }
+}
+
+/// A missing feerate is reported per target rather than failing the request.
+fn fee_target(target: u64, est: SmartFeeEstimate) -> FeeTarget {
+    FeeTarget {
+        target,
+        blocks: est.blocks,
+        // BTC/kvB -> sat/vB
+        feerate_sat_vb: est.feerate.map(|r| r * 100_000_000.0 / 1000.0),
+        errors: est.errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_form_yields_null_feerate() {
+        let est: SmartFeeEstimate = serde_json::from_str(
+            r#"{"errors":["Insufficient data or no feerate found"],"blocks":2}"#,
+        )
+        .unwrap();
+        let t = fee_target(1, est);
+        assert_eq!(t.target, 1);
+        assert_eq!(t.blocks, 2);
+        assert!(t.feerate_sat_vb.is_none());
+        assert_eq!(t.errors, vec!["Insufficient data or no feerate found".to_string()]);
+    }
+
+    #[test]
+    fn recommended_tiers_are_monotonic() {
+        let fees = recommended_tiers([Some(20.4), Some(25.0), None, Some(3.2)], 1.0);
+        assert_eq!(
+            fees,
+            RecommendedFees { fastest: 20, half_hour: 20, hour: 20, economy: 3, minimum: 1 }
+        );
+
+        let fees = recommended_tiers([Some(12.0), Some(8.0), Some(5.0), Some(0.5)], 2.0);
+        assert_eq!(
+            fees,
+            RecommendedFees { fastest: 12, half_hour: 8, hour: 5, economy: 2, minimum: 2 }
+        );
+    }
+
+    #[test]
+    fn missing_tiers_borrow_a_neighbour() {
+        let fees = recommended_tiers([None, Some(15.0), Some(9.0), None], 2.0);
+        assert_eq!(
+            fees,
+            RecommendedFees { fastest: 15, half_hour: 15, hour: 9, economy: 9, minimum: 2 }
+        );
+
+        let fees = recommended_tiers([None; 4], 3.0);
+        assert_eq!(
+            fees,
+            RecommendedFees { fastest: 3, half_hour: 3, hour: 3, economy: 3, minimum: 3 }
+        );
+    }
+
+    #[test]
+    fn depth_counts_vsize_at_or_above_rate() {
+        let hist = [(50.0, 400_000), (20.0, 900_000), (5.0, 3_000_000), (1.0, 500_000_000)];
+        let d = depth_for(&hist, 20.0);
+        assert_eq!(d.vsize_ahead, 1_300_000);
+        assert_eq!(d.est_blocks, 2);
+        assert!(!d.capped);
+
+        let d = depth_for(&hist, 1.0);
+        assert_eq!(d.est_blocks, MAX_DEPTH_BLOCKS);
+        assert!(d.capped);
+    }
+
+    #[test]
+    fn feerate_form_converts_to_sat_vb() {
+        let est: SmartFeeEstimate =
+            serde_json::from_str(r#"{"feerate":0.00012,"blocks":3}"#).unwrap();
+        let t = fee_target(3, est);
+        assert!((t.feerate_sat_vb.unwrap() - 12.0).abs() < 1e-9);
+        assert!(t.errors.is_empty());
+    }
+}
@@ -1,14 +1,85 @@
 use std::sync::Arc;
 
-use axum::{extract::State, Json};
-use crate::{models::MempoolInfo, rpc::rpc_call, state::AppState, utils::internalize};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use crate::{
+    models::{MempoolHistoryQ, MempoolInfo, MempoolPoint, MempoolTxidsQ, RecentMempoolTx},
+    rpc::rpc_call,
+    state::AppState,
+    utils::internalize,
+};
+
+/// Refuse the verbose mempool dump above this many transactions.
+pub const MAX_VERBOSE_TXS: u64 = 50_000;
+
+/// Default window for `/api/mempool/history`.
+const DEFAULT_HISTORY_MINUTES: u64 = 60;
 
 pub async fn mempoolinfo(
     State(st): State<Arc<AppState>>,
 ) -> Result<Json<MempoolInfo>, (axum::http::StatusCode, String)> {
-    let params = serde_json::json!([]);
-    rpc_call::<MempoolInfo>(&st, "getmempoolinfo", params)
+    mempool_info(&st).await.map(Json)
+}
+
+/// `getmempoolinfo`, from the sampler's snapshot when it is fresh.
+pub async fn mempool_info(st: &AppState) -> Result<MempoolInfo, (StatusCode, String)> {
+    if let Some(snap) = st.mempool.latest() {
+        return Ok(snap.info.clone());
+    }
+    rpc_call(st, "getmempoolinfo", serde_json::json!([]))
+        .await
+        .map_err(internalize)
+}
+
+/// `getrawmempool`: a plain array of txids, or with `?verbose=1` the entries
+/// keyed by txid. The verbose form is heavy (roughly 500 bytes per tx), so it
+/// is refused when the mempool holds more than `MAX_VERBOSE_TXS` transactions.
+pub async fn mempool_txids(
+    State(st): State<Arc<AppState>>,
+    Query(q): Query<MempoolTxidsQ>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let verbose = q.verbose.unwrap_or(0) != 0;
+
+    if verbose {
+        let info = mempool_info(&st).await?;
+        if info.size > MAX_VERBOSE_TXS {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "mempool has {} txs; verbose listing is limited to {MAX_VERBOSE_TXS}",
+                    info.size
+                ),
+            ));
+        }
+    }
+
+    rpc_call::<serde_json::Value>(&st, "getrawmempool", serde_json::json!([verbose]))
         .await
         .map(Json)
         .map_err(internalize)
 }
+
+/// Newest mempool entries: live from the ZMQ `hashtx` feed when configured,
+/// else from the sampler's last snapshot.
+pub async fn recent(
+    State(st): State<Arc<AppState>>,
+) -> Result<Json<Vec<RecentMempoolTx>>, (StatusCode, String)> {
+    if let Some(txs) = st.zmq.recent() {
+        return Ok(Json(txs));
+    }
+    st.mempool
+        .latest()
+        .map(|snap| Json(snap.recent.clone()))
+        .ok_or_else(|| (StatusCode::SERVICE_UNAVAILABLE, "no recent mempool snapshot yet".to_string()))
+}
+
+/// Sampler history points from the last `?minutes=` (default 60), oldest first.
+pub async fn history(
+    State(st): State<Arc<AppState>>,
+    Query(q): Query<MempoolHistoryQ>,
+) -> Json<Vec<MempoolPoint>> {
+    Json(st.mempool.history(q.minutes.unwrap_or(DEFAULT_HISTORY_MINUTES)))
+}
@@ -3,3 +3,6 @@ pub mod mempool;
 pub mod network;
 pub mod blocks;
 pub mod tx;
+pub mod fees;
+pub mod address;
+pub mod convert;
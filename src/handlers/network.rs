@@ -1,70 +1,75 @@
 use std::sync::Arc;
 
-use axum::{extract::State, Json};
+use axum::{extract::State, http::StatusCode, Json};
 use crate::{
-    models::{BlockHeaderLite, ChainInfo, NetworkSummary},
+    difficulty::{epoch_start_height, estimate_retarget, RetargetEstimate},
+    models::{BlockHeaderLite, ChainInfo, DifficultyView, NetworkSummary, PeerInfo, PeersSummary, SupplyEra},
     rpc::rpc_call,
     state::AppState,
-    supply::{current_subsidy_btc, mined_supply_btc},
+    supply::{current_subsidy_btc, halving_schedule, mined_supply_btc},
     utils::internalize,
 };
 
-pub async fn network_summary(
-    State(st): State<Arc<AppState>>,
-) -> Result<Json<NetworkSummary>, (axum::http::StatusCode, String)> {
-    // 1) height + difficulty
-    let ci: ChainInfo = rpc_call(&st, "getblockchaininfo", serde_json::json!([]))
-        .await
-        .map_err(internalize)?;
-    let height = ci.blocks;
-    let difficulty = ci.difficulty;
-
-    // 2) tip header
-    let tip_hash: String = rpc_call(&st, "getblockhash", serde_json::json!([height]))
+/// `getblockchaininfo` plus the tip's header.
+async fn chain_tip(st: &AppState) -> Result<(ChainInfo, BlockHeaderLite), (StatusCode, String)> {
+    let ci: ChainInfo = rpc_call(st, "getblockchaininfo", serde_json::json!([]))
         .await
         .map_err(internalize)?;
-    let tip_hdr: BlockHeaderLite = rpc_call(&st, "getblockheader", serde_json::json!([tip_hash, true]))
+    let tip_hdr: BlockHeaderLite = rpc_call(st, "getblockheader", serde_json::json!([ci.bestblockhash, true]))
         .await
         .map_err(internalize)?;
+    Ok((ci, tip_hdr))
+}
 
-    // 3) epoch stats
-    let epoch_len: u64 = 2016;
-    let blocks_into_epoch: u64 = height % epoch_len;
-    let blocks_to_next_adjust: u64 = epoch_len - blocks_into_epoch;
-
-    let (avg_block_interval_sec, est_diff_change_pct) = if blocks_into_epoch == 0 {
-        (600.0, 0.0)
+/// Fetch the epoch's first header (when needed) and project the retarget.
+async fn epoch_stats(st: &AppState, height: u64, tip_time: u64) -> Result<RetargetEstimate, (StatusCode, String)> {
+    let start_h = epoch_start_height(height);
+    let start_time = if start_h == height {
+        tip_time
     } else {
-        let start_h: u64 = height.saturating_sub(blocks_into_epoch);
-        let start_hash: String = rpc_call(&st, "getblockhash", serde_json::json!([start_h]))
+        let start_hash: String = rpc_call(st, "getblockhash", serde_json::json!([start_h]))
             .await
             .map_err(internalize)?;
-        let start_hdr: BlockHeaderLite = rpc_call(&st, "getblockheader", serde_json::json!([start_hash, true]))
+        let start_hdr: BlockHeaderLite = rpc_call(st, "getblockheader", serde_json::json!([start_hash, true]))
             .await
             .map_err(internalize)?;
+        start_hdr.time
+    };
+    Ok(estimate_retarget(height, tip_time, start_time))
+}
 
-        let blocks_so_far = blocks_into_epoch as f64;
-        let dt = (tip_hdr.time.saturating_sub(start_hdr.time)) as f64;
-        let avg_since_epoch = if dt > 0.0 { dt / blocks_so_far } else { 600.0 };
+pub async fn network_summary(
+    State(st): State<Arc<AppState>>,
+) -> Result<Json<NetworkSummary>, (axum::http::StatusCode, String)> {
+    if let Some(cached) = st.network_cache.get(&()) {
+        return Ok(Json(cached));
+    }
 
-        let ratio = 600.0 / avg_since_epoch;
-        let est_pct = ((ratio - 1.0) * 100.0).clamp(-50.0, 50.0);
+    // 1) height + difficulty + tip header
+    let (ci, tip_hdr) = chain_tip(&st).await?;
+    let height = ci.blocks;
+    let difficulty = ci.difficulty;
 
-        (avg_since_epoch, est_pct)
-    };
+    // 2) epoch stats
+    let RetargetEstimate {
+        blocks_into_epoch,
+        blocks_to_next_adjust,
+        avg_block_interval_sec,
+        est_diff_change_pct,
+    } = epoch_stats(&st, height, tip_hdr.time).await?;
 
-    // 4) network hashrate (H/s -> GH/s)
+    // 3) network hashrate (H/s -> GH/s)
     let nhps_hps: f64 = rpc_call(&st, "getnetworkhashps", serde_json::json!([]))
         .await
         .map_err(internalize)?;
     let hashrate_ghps = nhps_hps / 1e9;
 
-    // 5) supply
+    // 4) supply
     let curr_subsidy = current_subsidy_btc(height);
     let est_new_btc_per_day = curr_subsidy * 144.0;
     let est_circulating_btc = mined_supply_btc(height);
 
-    Ok(Json(NetworkSummary {
+    let summary = NetworkSummary {
         height,
         difficulty,
         hashrate_ghps,
@@ -76,5 +81,54 @@ pub async fn network_summary(
         est_new_btc_per_day,
         est_circulating_btc,
         tip_time: tip_hdr.time,
+    };
+    st.network_cache.insert((), summary.clone());
+    Ok(Json(summary))
+}
+
+pub async fn difficulty(
+    State(st): State<Arc<AppState>>,
+) -> Result<Json<DifficultyView>, (StatusCode, String)> {
+    if let Some(cached) = st.difficulty_cache.get(&()) {
+        return Ok(Json(cached));
+    }
+
+    let (ci, tip_hdr) = chain_tip(&st).await?;
+    let epoch = epoch_stats(&st, ci.blocks, tip_hdr.time).await?;
+
+    let view = DifficultyView {
+        current: ci.difficulty,
+        estimated_next: ci.difficulty * (1.0 + epoch.est_diff_change_pct / 100.0),
+        change_percent: epoch.est_diff_change_pct,
+        blocks_until_retarget: epoch.blocks_to_next_adjust,
+        estimated_retarget_time: tip_hdr.time
+            + (epoch.blocks_to_next_adjust as f64 * epoch.avg_block_interval_sec) as u64,
+    };
+    st.difficulty_cache.insert((), view.clone());
+    Ok(Json(view))
+}
+
+/// Full halving schedule; static, so no node call.
+pub async fn supply_schedule() -> Json<Vec<SupplyEra>> {
+    Json(halving_schedule())
+}
+
+pub async fn peers(
+    State(st): State<Arc<AppState>>,
+) -> Result<Json<PeersSummary>, (axum::http::StatusCode, String)> {
+    if !st.expose_peers {
+        return Err((axum::http::StatusCode::NOT_FOUND, "peer info is disabled".to_string()));
+    }
+
+    let peers: Vec<PeerInfo> = rpc_call(&st, "getpeerinfo", serde_json::json!([]))
+        .await
+        .map_err(internalize)?;
+    let inbound = peers.iter().filter(|p| p.inbound).count();
+
+    Ok(Json(PeersSummary {
+        count: peers.len(),
+        inbound,
+        outbound: peers.len() - inbound,
+        peers,
     }))
 }
@@ -1,4 +1,13 @@
-use axum::response::Html;
+use std::{sync::Arc, time::Instant};
+
+use axum::{extract::State, http::StatusCode, response::Html, Json};
+
+use crate::{
+    electrum,
+    models::{CachesView, ChainInfo, PingStatus, ReadyStatus},
+    rpc::rpc_call,
+    state::AppState,
+};
 
 pub async fn index() -> Html<String> {
     let html = include_str!("../../templates/index.html");
@@ -8,3 +17,62 @@ pub async fn index() -> Html<String> {
 pub async fn health() -> &'static str {
     "ok"
 }
+
+/// Readiness: Core answers RPC and the Electrum breaker is not open. ZMQ
+/// subscriptions are reported but do not gate readiness; the tip poller
+/// covers for them while they are down.
+pub async fn ready(State(st): State<Arc<AppState>>) -> (StatusCode, Json<ReadyStatus>) {
+    let rpc_ok = rpc_call::<ChainInfo>(&st, "getblockchaininfo", serde_json::json!([]))
+        .await
+        .is_ok();
+    let electrum_breaker = st.electrum_breaker.state();
+    let ready = rpc_ok && electrum_breaker != "open";
+
+    let code = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (code, Json(ReadyStatus { ready, rpc_ok, electrum_breaker, zmq: st.zmq.status() }))
+}
+
+/// Round-trip latencies of a cheap Core RPC and an Electrum `server.ping`.
+/// 503 if either backend fails, with the other latency still reported.
+pub async fn ping(State(st): State<Arc<AppState>>) -> (StatusCode, Json<PingStatus>) {
+    let started = Instant::now();
+    let rpc_ms = rpc_call::<ChainInfo>(&st, "getblockchaininfo", serde_json::json!([]))
+        .await
+        .ok()
+        .map(|_| started.elapsed().as_secs_f64() * 1000.0);
+
+    let electrum_ms = match electrum::permit(&st).await {
+        Ok(permit) => {
+            let st_blocking = st.clone();
+            tokio::task::spawn_blocking(move || -> anyhow::Result<f64> {
+                let _permit = permit;
+                let mut cli = electrum::connect(&st_blocking)?;
+                let started = Instant::now();
+                cli.ping()?;
+                Ok(started.elapsed().as_secs_f64() * 1000.0)
+            })
+            .await
+            .ok()
+            .and_then(Result::ok)
+        }
+        Err(_) => None,
+    };
+
+    let code = if rpc_ms.is_some() && electrum_ms.is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(PingStatus { rpc_ms, electrum_ms }))
+}
+
+/// Hit/miss counters of the in-memory caches.
+pub async fn cache_stats(State(st): State<Arc<AppState>>) -> Json<CachesView> {
+    Json(CachesView {
+        tip: st.tip_cache.stats(),
+        network: st.network_cache.stats(),
+        difficulty: st.difficulty_cache.stats(),
+        fees_recommended: st.fees_cache.stats(),
+        fee_table: st.fee_table_cache.stats(),
+    })
+}
@@ -1,17 +1,21 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
 
 use axum::{
     extract::{Path, Query, State},
+    http::StatusCode,
     Json,
 };
-use bitcoin::{Address, Network, Txid};
-use electrum_client::{Client as ElectrumClient, ElectrumApi};
+use bitcoin::{ScriptBuf, Transaction, Txid};
 
 use crate::{
-    models::{PrevoutResolved, ResolveQ, TxDecoded, TxView},
+    electrum,
+    models::{MempoolEntryLite, Outspend, PrevoutResolved, ResolveQ, TxDecodeReq, TxDecoded, TxView},
     rpc::rpc_call,
     state::AppState,
-    utils::{internalize, tx_is_coinbase, vout_value_btc},
+    utils::{internalize, tx_is_coinbase, validate_hash, vout_is_dust, vout_value_btc},
 };
 
 use std::str::FromStr;
@@ -21,6 +25,7 @@ pub async fn tx_by_id(
     Path(txid): Path<String>,
     Query(q): Query<ResolveQ>,
 ) -> Result<Json<TxView>, (axum::http::StatusCode, String)> {
+    validate_hash("txid", &txid)?;
     // 1) Main tx via Core (keeps confirmations/blockhash/vsize accurate)
     let tx: TxDecoded = rpc_call(&st, "getrawtransaction", serde_json::json!([txid, true]))
         .await
@@ -36,12 +41,10 @@ pub async fn tx_by_id(
     // 2) Outputs total
     let outputs_total_btc: f64 = tx.vout.iter().map(vout_value_btc).sum();
 
-    // 3) Prevouts list (capped)
+    // 3) Prevouts list (capped; none with ?verify_inputs=0)
     let total_inputs = tx.vin.len();
-    let cap_max = 100usize;
-    let mut resolve_n = q.resolve.unwrap_or(20);
-    if resolve_n > cap_max { resolve_n = cap_max; }
-    if resolve_n > total_inputs { resolve_n = total_inputs; }
+    let verify_inputs = q.verify_inputs.unwrap_or(true);
+    let resolve_n = if verify_inputs { st.resolve_cap(q.resolve).min(total_inputs) } else { 0 };
 
     let mut prev_pairs: Vec<(String, u32)> = Vec::with_capacity(resolve_n);
     for vin in tx.vin.iter().take(resolve_n) {
@@ -54,10 +57,14 @@ pub async fn tx_by_id(
     }
 
     // 4) Resolve prevouts via Electrs in spawn_blocking
-    let electrs_addr = st.electrs_addr.clone();
-    let (inputs_resolved, inputs_total_btc) =
+    let (inputs_resolved, inputs_total_btc) = if !verify_inputs {
+        (Vec::new(), None)
+    } else {
+        let permit = electrum::permit(&st).await?;
+        let st_blocking = st.clone();
         tokio::task::spawn_blocking(move || -> anyhow::Result<(Vec<PrevoutResolved>, Option<f64>)> {
-            let cli = ElectrumClient::new(&format!("tcp://{}", electrs_addr))?;
+            let _permit = permit;
+            let mut cli = electrum::connect(&st_blocking)?;
 
             let mut out = Vec::<PrevoutResolved>::with_capacity(prev_pairs.len());
             let mut sum_inputs_sats: u128 = 0;
@@ -66,26 +73,15 @@ pub async fn tx_by_id(
                 let prev_txid = Txid::from_str(&prev_txid_str)
                     .map_err(|e| anyhow::anyhow!("bad prev txid {}: {}", prev_txid_str, e))?;
 
-                // Fetch previous tx (bitcoin::Transaction)
-                let prev = cli.transaction_get(&prev_txid)?;
-                let vout = prev
-                    .output
-                    .get(vout_idx as usize)
-                    .ok_or_else(|| anyhow::anyhow!("prevout index {} out of range", vout_idx))?;
-
-                // Amount is `Amount`; convert to primitive sats
-                let val_sats: u64 = vout.value.to_sat();
+                // Fetch previous tx output: value in sats + rendered address
+                let (val_sats, addr) = electrum::fetch_prevout(&mut cli, &prev_txid, vout_idx)?;
                 sum_inputs_sats += val_sats as u128;
 
-                // Try to render address from script
-                let addr = Address::from_script(&vout.script_pubkey, Network::Bitcoin)
-                    .map(|a| a.to_string())
-                    .unwrap_or_else(|_| "(no address)".to_string()); // <- accept error arg
-
                 out.push(PrevoutResolved {
                     txid: prev_txid_str,
                     vout: vout_idx,
                     value_btc: (val_sats as f64) / 100_000_000.0, // sats → BTC
+                    label: st_blocking.label_for(&addr),
                     address: addr,
                 });
             }
@@ -100,10 +96,17 @@ pub async fn tx_by_id(
         })
         .await
         .map_err(|e| internalize(format!("electrum task failed: {e}")))?
-        .map_err(internalize)?;
+        .map_err(|e| {
+            tracing::warn!(error = %e, "electrum prevout resolution failed");
+            internalize(e)
+        })?
+    };
 
-    // 5) Fee & feerate
-    let fee_btc = inputs_total_btc.map(|ins| (ins - outputs_total_btc).max(0.0));
+    // 5) Fee & feerate (unknowable unless every input was resolved)
+    let more_inputs = verify_inputs && total_inputs > resolve_n;
+    let fee_btc = inputs_total_btc
+        .filter(|_| !more_inputs)
+        .map(|ins| (ins - outputs_total_btc).max(0.0));
     let feerate_sat_vb = match (fee_btc, tx.vsize) {
         (Some(fee_btc), Some(vsize)) if vsize > 0 => {
             let fee_sats = fee_btc * 100_000_000.0;
@@ -115,29 +118,185 @@ pub async fn tx_by_id(
     // 6) Coinbase?
     let is_cb = tx_is_coinbase(&tx);
 
-    // 7) Response (unchanged shape)
+    // 7) Enrich outputs: dust flag and known address labels
+    let mut vout = tx.vout;
+    for v in vout.iter_mut() {
+        let is_dust = vout_is_dust(v);
+        let label = v
+            .pointer("/scriptPubKey/address")
+            .and_then(|a| a.as_str())
+            .and_then(|a| st.label_for(a));
+        if let Some(obj) = v.as_object_mut() {
+            obj.insert("is_dust".to_string(), serde_json::Value::Bool(is_dust));
+            if let Some(label) = label {
+                obj.insert("label".to_string(), serde_json::Value::String(label));
+            }
+        }
+    }
+
+    // 8) First seen: mempool entry time while unconfirmed, blocktime after.
+    // If it confirms between the two calls the entry is gone; re-read the tx
+    // and take all confirmation fields from that read so they agree.
+    let (first_seen, confirmations, blockhash) = if tx.confirmations.unwrap_or(0) > 0 {
+        (tx.blocktime, tx.confirmations, tx.blockhash)
+    } else {
+        match rpc_call::<MempoolEntryLite>(&st, "getmempoolentry", serde_json::json!([tx.txid])).await {
+            Ok(entry) => (Some(entry.time), tx.confirmations, tx.blockhash),
+            Err(_) => match rpc_call::<TxDecoded>(&st, "getrawtransaction", serde_json::json!([tx.txid, true])).await {
+                Ok(t) => (t.blocktime, t.confirmations, t.blockhash),
+                Err(_) => (None, tx.confirmations, tx.blockhash),
+            },
+        }
+    };
+
+    // 9) Response (unchanged shape)
     let view = TxView {
         txid: tx.txid.clone(),
         size: tx.size,
         vsize: tx.vsize,
         weight: tx.weight,
-        confirmations: tx.confirmations,
-        blockhash: tx.blockhash,
+        confirmations,
+        blockhash,
         is_coinbase: is_cb,
+        first_seen,
 
         inputs_resolved,
         inputs_total_btc,
         outputs_total_btc,
         fee_btc,
         feerate_sat_vb,
+        fee_estimated: fee_btc.is_some(),
 
+        inputs_verified: verify_inputs,
         total_inputs,
         resolved_inputs: resolve_n,
-        more_inputs: total_inputs > resolve_n,
+        more_inputs,
 
-        vout: tx.vout,
+        vout,
     };
 
     Ok(Json(view))
 }
 
+/// Outspends walk one Electrum history per distinct output script; refuse
+/// transactions with more outputs than this.
+const MAX_OUTSPENDS: usize = 100;
+/// Candidate spenders fetched per request; reused addresses can have huge
+/// histories, so give up past this many rather than fetch them all.
+const MAX_OUTSPEND_FETCHES: usize = 500;
+
+pub async fn tx_outspends(
+    State(st): State<Arc<AppState>>,
+    Path(txid): Path<String>,
+) -> Result<Json<Vec<Outspend>>, (StatusCode, String)> {
+    validate_hash("txid", &txid)?;
+    let txid = Txid::from_str(&txid)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid txid {txid}: {e}")))?;
+
+    let permit = electrum::permit(&st).await?;
+    let st_blocking = st.clone();
+    let outspends = tokio::task::spawn_blocking(move || -> anyhow::Result<Result<Vec<Outspend>, (StatusCode, String)>> {
+        let _permit = permit;
+        let mut cli = electrum::connect(&st_blocking)?;
+
+        let tx = match cli.transaction_get(&txid) {
+            Ok(tx) => tx,
+            Err(e) if electrum::is_not_found(&e) => {
+                return Ok(Err((StatusCode::NOT_FOUND, format!("tx not found: {e}"))));
+            }
+            Err(e) => return Err(e),
+        };
+        if tx.output.len() > MAX_OUTSPENDS {
+            return Ok(Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("tx has {} outputs; outspends is limited to {MAX_OUTSPENDS}", tx.output.len()),
+            )));
+        }
+
+        let mut out: Vec<Outspend> = tx.output.iter().map(|_| Outspend::default()).collect();
+        let mut histories: HashMap<ScriptBuf, Vec<(Txid, i32)>> = HashMap::new();
+        let mut fetched: HashMap<Txid, Transaction> = HashMap::new();
+        // the tx's own height, from the first history that lists it
+        let mut own_height: Option<i32> = None;
+
+        for (n, o) in tx.output.iter().enumerate() {
+            if o.script_pubkey.is_op_return() {
+                continue;
+            }
+            if !histories.contains_key(&o.script_pubkey) {
+                let mut hist = Vec::new();
+                for h in cli.script_get_history(&o.script_pubkey)? {
+                    if h.tx_hash == txid {
+                        own_height.get_or_insert(h.height);
+                    } else {
+                        hist.push((h.tx_hash, h.height));
+                    }
+                }
+                histories.insert(o.script_pubkey.clone(), hist);
+            }
+
+            // a spender confirms no earlier than the tx itself, or is still in the mempool
+            let candidates = histories[&o.script_pubkey].iter().filter(|(_, height)| match own_height {
+                Some(own) if own > 0 => *height <= 0 || *height >= own,
+                Some(_) => *height <= 0,
+                None => true,
+            });
+            for (cand_txid, height) in candidates {
+                if !fetched.contains_key(cand_txid) {
+                    if fetched.len() >= MAX_OUTSPEND_FETCHES {
+                        return Ok(Err((
+                            StatusCode::UNPROCESSABLE_ENTITY,
+                            format!("outputs have too many candidate spenders; limited to {MAX_OUTSPEND_FETCHES}"),
+                        )));
+                    }
+                    fetched.insert(*cand_txid, cli.transaction_get(cand_txid)?);
+                }
+                let spender = fetched[cand_txid].input.iter().position(|i| {
+                    i.previous_output.txid == txid && i.previous_output.vout as usize == n
+                });
+                if let Some(vin) = spender {
+                    out[n] = Outspend {
+                        spent: true,
+                        txid: Some(cand_txid.to_string()),
+                        vin: Some(vin as u32),
+                        confirmed: *height > 0,
+                    };
+                    break;
+                }
+            }
+        }
+        Ok(Ok(out))
+    })
+    .await
+    .map_err(|e| internalize(format!("electrum task failed: {e}")))?
+    .map_err(|e| {
+        tracing::warn!(error = %e, "electrum outspends failed");
+        internalize(e)
+    })??;
+
+    Ok(Json(outspends))
+}
+
+/// Decode a raw transaction the caller holds; it need not be known to the node.
+pub async fn tx_decode(
+    State(st): State<Arc<AppState>>,
+    Json(req): Json<TxDecodeReq>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let hex = req.hex.trim();
+    if hex.is_empty() || hex::decode(hex).is_err() {
+        return Err((StatusCode::BAD_REQUEST, "hex must be a non-empty, even-length hex string".to_string()));
+    }
+
+    rpc_call(&st, "decoderawtransaction", serde_json::json!([hex]))
+        .await
+        .map(Json)
+        .map_err(|e| {
+            let msg = e.to_string();
+            // RPC_DESERIALIZATION_ERROR: the bytes are not a valid transaction
+            if msg.contains("rpc error -22") {
+                (StatusCode::BAD_REQUEST, format!("tx decode failed: {msg}"))
+            } else {
+                internalize(msg)
+            }
+        })
+}
@@ -1,56 +1,147 @@
 use anyhow::{Context, Result};
-use axum::{routing::get, Router};
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware,
+    routing::{get, post},
+    Router,
+};
 use dotenvy::dotenv;
-use std::{net::SocketAddr, sync::Arc};
+use std::{sync::Arc, time::Duration};
 use tokio::net::TcpListener;
-use tower_http::services::ServeDir;
+use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    services::ServeDir,
+    trace::{DefaultOnResponse, TraceLayer},
+};
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
 
+mod config;
 mod state;
+mod cache;
+mod electrum;
 mod rpc;
 mod models;
 mod supply;
+mod difficulty;
 mod utils;
 mod handlers;
+mod tasks;
+mod request_id;
+mod decimals;
+mod pretty;
+mod static_cache;
+mod send_timeout;
+mod zmtp;
 
+use config::Config;
+use send_timeout::SendTimeoutListener;
 use state::AppState;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
 
-    let rpc_url = std::env::var("RPC_URL").context("missing RPC_URL")?;
-    let rpc_user = std::env::var("RPC_USER").context("missing RPC_USER")?;
-    let rpc_pass = std::env::var("RPC_PASS").context("missing RPC_PASS")?;
-    let bind_addr: SocketAddr = std::env::var("BIND_ADDR")
-        .unwrap_or_else(|_| "0.0.0.0:8000".to_string())
-        .parse()
-        .context("BIND_ADDR must be host:port")?;
+    let cfg = Config::load()?;
 
-    let electrs_addr = std::env::var("ELECTRS_ADDR").unwrap_or_else(|_| "127.0.0.1:50001".to_string());
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    if cfg.log_json {
+        tracing_subscriber::fmt()
+            .json()
+            .with_current_span(true)
+            .with_env_filter(filter)
+            .init();
+    } else {
+        tracing_subscriber::fmt().with_env_filter(filter).init();
+    }
 
-    let state = Arc::new(AppState::new(
-        rpc_url,
-        rpc_user,
-        rpc_pass,
-        electrs_addr,
-    ));
+    let bind_addr = cfg.bind_addr;
+    let static_cache_secs = cfg.static_cache_secs;
+    let send_timeout = Duration::from_secs(cfg.send_timeout_secs.max(1));
+    let tip_poll_secs = cfg.tip_poll_secs;
+    let state = Arc::new(AppState::new(&cfg)?);
+    tokio::spawn(tasks::mempool::run(state.clone()));
+    if let Some(addr) = cfg.zmq_hashblock_addr.clone() {
+        tokio::spawn(tasks::zmq::run(state.clone(), "hashblock", addr));
+    }
+    if let Some(addr) = cfg.zmq_hashtx_addr.clone() {
+        tokio::spawn(tasks::zmq::run(state.clone(), "hashtx", addr));
+    }
+    if tip_poll_secs > 0 {
+        tokio::spawn(tasks::tip::run(state.clone(), Duration::from_secs(tip_poll_secs)));
+    }
 
     let app = Router::new()
         // pages
         .route("/", get(handlers::pages::index))
         .route("/health", get(handlers::pages::health))
+        .route("/health/ready", get(handlers::pages::ready))
+        .route("/api/ping", get(handlers::pages::ping))
+        .route("/api/cache/stats", get(handlers::pages::cache_stats))
         // api
         .route("/api/mempoolinfo", get(handlers::mempool::mempoolinfo))
+        .route("/api/mempool/txids", get(handlers::mempool::mempool_txids))
+        .route("/api/mempool/recent", get(handlers::mempool::recent))
+        .route("/api/mempool/history", get(handlers::mempool::history))
+        .route("/api/fees", get(handlers::fees::fee_estimates))
+        .route("/api/fees/recommended", get(handlers::fees::recommended))
+        .route("/api/fees/depth", get(handlers::fees::depth))
         .route("/api/network", get(handlers::network::network_summary))
+        .route("/api/network/difficulty", get(handlers::network::difficulty))
+        .route("/api/network/peers", get(handlers::network::peers))
+        .route("/api/supply/schedule", get(handlers::network::supply_schedule))
         .route("/api/blockhash/{height}", get(handlers::blocks::blockhash_by_height))
+        .route("/api/block/tip", get(handlers::blocks::block_tip))
         .route("/api/block/{hash}", get(handlers::blocks::block_by_hash))
+        .route("/api/block/{hash}/coinbase", get(handlers::blocks::block_coinbase))
+        .route("/api/block/{hash}/headerhex", get(handlers::blocks::block_header_hex))
+        .route("/api/tx/decode", post(handlers::tx::tx_decode))
         .route("/api/tx/{txid}", get(handlers::tx::tx_by_id))
+        .route("/api/tx/{txid}/outspends", get(handlers::tx::tx_outspends))
+        .route("/api/convert", get(handlers::convert::convert))
+        .route("/api/addr/{address}/txs", get(handlers::address::addr_txs))
+        .route("/api/addr/{address}/used", get(handlers::address::addr_used))
+        .route("/api/addr/{address}/mempool", get(handlers::address::addr_mempool))
         // static
-        .nest_service("/static", ServeDir::new("static"))
+        .nest_service(
+            "/static",
+            Router::new()
+                .fallback_service(ServeDir::new("static"))
+                .layer(middleware::from_fn(move |req, next| {
+                    static_cache::cache_control(static_cache_secs, req, next)
+                })),
+        )
         // shared state
-        .with_state(state);
+        .with_state(state)
+        // ?decimals=N formatting of BTC amounts
+        .layer(middleware::from_fn(decimals::format_btc))
+        // ?pretty=1 indented JSON (runs after decimals formatting)
+        .layer(middleware::from_fn(pretty::pretty_json))
+        // request ids: assigned first, echoed as X-Request-Id, carried on the trace span
+        .layer(middleware::from_fn(request_id::tag_errors))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(|req: &Request| {
+                    let route = req
+                        .extensions()
+                        .get::<MatchedPath>()
+                        .map(|p| p.as_str().to_string())
+                        .unwrap_or_default();
+                    tracing::info_span!(
+                        "request",
+                        request_id = %request_id::request_id_of(req),
+                        method = %req.method(),
+                        route,
+                        uri = %req.uri(),
+                    )
+                })
+                // status + latency per request
+                .on_response(DefaultOnResponse::new().level(Level::INFO)),
+        )
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid));
 
-    println!("listening on http://{bind_addr}");
-    let listener = TcpListener::bind(bind_addr).await?;
+    tracing::info!("listening on http://{bind_addr}");
+    let listener = SendTimeoutListener::new(TcpListener::bind(bind_addr).await?, send_timeout);
     axum::serve(listener, app).await.context("server crashed")
-}
\ No newline at end of file
+}
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 
 /// Minimal header we read from `getblockheader`
 #[derive(Deserialize, Serialize)]
@@ -7,15 +7,61 @@ pub struct BlockHeaderLite {
     pub height: Option<u64>,
 }
 
+/// Response for `/health/ready`
+#[derive(Serialize)]
+pub struct ReadyStatus {
+    pub ready: bool,
+    pub rpc_ok: bool,
+    pub electrum_breaker: &'static str,
+    pub zmq: Vec<ZmqSocketStatus>, // empty unless ZMQ_* endpoints are configured
+}
+
+/// One bitcoind ZMQ subscription in `/health/ready`
+#[derive(Serialize, Clone)]
+pub struct ZmqSocketStatus {
+    pub topic: &'static str,
+    pub addr: String,
+    pub connected: bool,
+    pub reconnects: u64,
+    pub last_message_at: Option<u64>, // UNIX seconds
+    pub last_error: Option<String>,
+}
+
+/// `/api/ping` latencies; null when that backend did not answer
+#[derive(Serialize)]
+pub struct PingStatus {
+    pub rpc_ms: Option<f64>,
+    pub electrum_ms: Option<f64>,
+}
+
+/// Counters for one in-memory cache
+#[derive(Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// `/api/cache/stats`, one entry per cache
+#[derive(Serialize)]
+pub struct CachesView {
+    pub tip: CacheStats,
+    pub network: CacheStats,
+    pub difficulty: CacheStats,
+    pub fees_recommended: CacheStats,
+    pub fee_table: CacheStats,
+}
+
 /// `getblockchaininfo` subset we need
 #[derive(Deserialize, Serialize)]
 pub struct ChainInfo {
     pub blocks: u64,
+    pub bestblockhash: String,
     pub difficulty: f64,
 }
 
 /// `getmempoolinfo`
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct MempoolInfo {
     pub size: u64,
     pub bytes: u64,
@@ -25,8 +71,113 @@ pub struct MempoolInfo {
     #[serde(default)] pub mempoolminfee: f64,
 }
 
-/// API response for `/api/network`
+/// `estimatesmartfee`: `feerate` is absent (and `errors` set) when Core lacks data
 #[derive(Deserialize, Serialize)]
+pub struct SmartFeeEstimate {
+    pub feerate: Option<f64>, // BTC/kvB
+    #[serde(default)]
+    pub errors: Vec<String>,
+    pub blocks: u64,
+}
+
+/// One confirmation target in `/api/fees`
+#[derive(Serialize, Clone)]
+pub struct FeeTarget {
+    pub target: u64,
+    pub blocks: u64,
+    pub feerate_sat_vb: Option<f64>,
+    pub errors: Vec<String>,
+}
+
+/// API response for `/api/fees`
+#[derive(Serialize, Clone)]
+pub struct FeeEstimates {
+    pub estimates: Vec<FeeTarget>,
+    pub computed_at: u64, // UNIX seconds
+}
+
+/// Query params for `/api/fees`
+#[derive(Deserialize)]
+pub struct FeesQ {
+    pub fresh: Option<u8>, // 1 = bypass the cache
+}
+
+/// API response for `/api/fees/recommended` (whole sat/vB)
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RecommendedFees {
+    pub fastest: u64,
+    pub half_hour: u64,
+    pub hour: u64,
+    pub economy: u64,
+    pub minimum: u64,
+}
+
+/// Query params for `/api/mempool/txids`
+#[derive(Deserialize)]
+pub struct MempoolTxidsQ {
+    pub verbose: Option<u8>,
+}
+
+/// Query params for `/api/fees/depth`
+#[derive(Deserialize)]
+pub struct FeeDepthQ {
+    pub feerate: f64, // sat/vB
+}
+
+/// API response for `/api/fees/depth` (a rough estimate, not a guarantee)
+#[derive(Serialize)]
+pub struct FeeDepth {
+    pub feerate_sat_vb: f64,
+    pub vsize_ahead: u64,
+    pub est_blocks: u64,
+    pub capped: bool, // true when the estimate hit the maximum
+}
+
+/// `getmempoolentry` subset we need
+#[derive(Deserialize)]
+pub struct MempoolEntryLite {
+    pub time: u64, // UNIX seconds the node first saw the tx
+}
+
+/// `getrawmempool true` entry subset the mempool sampler needs
+#[derive(Deserialize)]
+pub struct MempoolEntry {
+    pub vsize: u64,
+    pub time: u64,
+    pub fees: Option<MempoolEntryFees>,
+}
+
+#[derive(Deserialize)]
+pub struct MempoolEntryFees {
+    pub base: f64, // BTC
+}
+
+/// Newest mempool entries in `/api/mempool/recent`
+#[derive(Serialize, Clone)]
+pub struct RecentMempoolTx {
+    pub txid: String,
+    pub time: u64, // UNIX seconds the tx was first seen
+    pub vsize: Option<u64>, // vsize and fee are null for entries from the ZMQ feed
+    pub fee_sats: Option<u64>,
+}
+
+/// One sampler tick in `/api/mempool/history`
+#[derive(Serialize, Clone)]
+pub struct MempoolPoint {
+    pub time: u64, // UNIX seconds
+    pub count: u64,
+    pub vbytes: u64,
+    pub min_fee_sat_vb: f64,
+}
+
+/// Query params for `/api/mempool/history`
+#[derive(Deserialize)]
+pub struct MempoolHistoryQ {
+    pub minutes: Option<u64>,
+}
+
+/// API response for `/api/network`
+#[derive(Deserialize, Serialize, Clone)]
 pub struct NetworkSummary {
     pub height: u64,
     pub difficulty: f64,
@@ -44,6 +195,48 @@ pub struct NetworkSummary {
     pub tip_time: u64,
 }
 
+/// API response for `/api/network/difficulty`
+#[derive(Serialize, Clone)]
+pub struct DifficultyView {
+    pub current: f64,
+    pub estimated_next: f64,
+    pub change_percent: f64,
+    pub blocks_until_retarget: u64,
+    pub estimated_retarget_time: u64, // UNIX seconds
+}
+
+/// One halving era of `/api/supply/schedule`
+#[derive(Serialize)]
+pub struct SupplyEra {
+    pub era: u64,
+    pub start_height: u64,
+    pub subsidy_btc: f64,
+    pub issued_btc: f64,     // over the whole era (genesis excluded)
+    pub cumulative_btc: f64, // at the era's last block
+}
+
+/// `getpeerinfo` entry, trimmed to what `/api/network/peers` shows
+#[derive(Deserialize, Serialize)]
+pub struct PeerInfo {
+    pub addr: String,
+    #[serde(default)]
+    pub subver: String,
+    pub inbound: bool,
+    #[serde(default)]
+    pub synced_blocks: i64,
+    #[serde(alias = "pingtime")]
+    pub ping: Option<f64>, // seconds
+}
+
+/// API response for `/api/network/peers`
+#[derive(Serialize)]
+pub struct PeersSummary {
+    pub count: usize,
+    pub inbound: usize,
+    pub outbound: usize,
+    pub peers: Vec<PeerInfo>,
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct GetBlockV1 {
     pub hash: String,
@@ -54,6 +247,7 @@ pub struct GetBlockV1 {
     pub weight: Option<u64>,
     #[serde(rename = "nTx")]
     pub n_tx: u64,
+    pub confirmations: i64, // -1 when the block is not on the main chain
     pub prevblockhash: Option<String>,
     pub nextblockhash: Option<String>,
     pub tx: Vec<String>, // <— txids only
@@ -66,6 +260,14 @@ pub struct BlockHashResp {
     pub hash: String,
 }
 
+/// Response for `/api/block/tip`
+#[derive(Serialize, Clone)]
+pub struct BlockTip {
+    pub height: u64,
+    pub hash: String,
+    pub time: u64,
+}
+
 /// Query params for block tx pagination
 #[derive(Deserialize)]
 pub struct BlockPageQ {
@@ -82,7 +284,9 @@ pub struct BlockView {
     pub mediantime: Option<u64>,
     pub size: u64,
     pub weight: Option<u64>,
+    pub weight_percent: Option<f64>, // share of the 4M WU consensus limit
     pub n_tx: u64,
+    pub confirmations: i64, // -1 for a stale (orphaned) block, as Core reports
     pub prev: Option<String>,
     pub next: Option<String>,
 
@@ -95,6 +299,48 @@ pub struct BlockView {
     pub limit: usize,
 }
 
+/// One output of a decoded coinbase
+#[derive(Serialize)]
+pub struct CoinbaseOutput {
+    pub n: usize,
+    pub value_btc: f64,
+    pub address: Option<String>,
+    pub label: Option<String>,
+    pub script_type: Option<String>,
+}
+
+/// Response for `/api/block/{hash}/coinbase`
+#[derive(Serialize)]
+pub struct CoinbaseView {
+    pub txid: String,
+    pub block_hash: String,
+    pub height: u64,
+    pub script_sig_hex: String,
+    pub bip34_height: Option<u64>,
+    pub miner_tag: Option<String>,
+    pub outputs: Vec<CoinbaseOutput>,
+    pub total_reward_btc: f64,
+    pub subsidy_btc: f64,
+    pub fees_btc: f64,
+}
+
+/// Query params for `/api/convert`
+#[derive(Deserialize)]
+pub struct ConvertQ {
+    pub amount: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Response for `/api/convert` (amounts as strings to stay exact)
+#[derive(Serialize)]
+pub struct ConvertResp {
+    pub amount: String,
+    pub from: String,
+    pub to: String,
+    pub result: String,
+}
+
 /// `getrawtransaction` decoded (verbosity=true)
 #[derive(Deserialize, Serialize, Clone)]
 pub struct TxDecoded {
@@ -120,6 +366,7 @@ pub struct PrevoutResolved {
     pub vout: u32,
     pub value_btc: f64,
     pub address: String,
+    pub label: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -131,21 +378,118 @@ pub struct TxView {
     pub confirmations: Option<u64>,
     pub blockhash: Option<String>,
     pub is_coinbase: bool,
+    pub first_seen: Option<u64>, // mempool entry time, or blocktime once confirmed
 
     pub inputs_resolved: Vec<PrevoutResolved>,
     pub inputs_total_btc: Option<f64>,
     pub outputs_total_btc: f64,
     pub fee_btc: Option<f64>,
     pub feerate_sat_vb: Option<f64>,
+    pub fee_estimated: bool, // false when not every input was resolved
 
+    pub inputs_verified: bool, // false with ?verify_inputs=0
     pub total_inputs: usize,
     pub resolved_inputs: usize,
-    pub more_inputs: bool,
+    pub more_inputs: bool, // some inputs left unresolved by the caps
 
     pub vout: Vec<serde_json::Value>,
 }
 
+/// Response for `/api/addr/{address}/used`
+#[derive(Serialize)]
+pub struct AddrUsed {
+    pub address: String,
+    pub used: bool,
+    pub tx_count: usize,
+}
+
+/// An unconfirmed tx touching an address, with its net effect on it
+#[derive(Serialize)]
+pub struct AddrMempoolTx {
+    pub txid: String,
+    pub delta_sats: i64, // received minus spent by this address
+    pub delta_btc: f64,
+    pub fee_sats: Option<u64>,
+}
+
+/// Response for `/api/addr/{address}/mempool`
+#[derive(Serialize)]
+pub struct AddrMempool {
+    pub address: String,
+    pub txs: Vec<AddrMempoolTx>,
+    pub more_txs: bool,
+}
+
+/// Query params for `/api/addr/{address}/txs`
+#[derive(Deserialize)]
+pub struct AddrTxsQ {
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+    pub resolve: Option<usize>, // inputs per tx, capped like `/api/tx`
+}
+
+/// One output of a transaction in an address activity feed
+#[derive(Serialize)]
+pub struct AddrTxOutput {
+    pub n: usize,
+    pub value_btc: f64,
+    pub address: String,
+    pub label: Option<String>,
+}
+
+/// A transaction touching an address, with both sides resolved
+#[derive(Serialize)]
+pub struct AddrTx {
+    pub txid: String,
+    pub height: Option<u64>, // None while in the mempool
+    pub confirmed: bool,
+    pub is_coinbase: bool,
+    pub inputs: Vec<PrevoutResolved>,
+    pub more_inputs: bool, // some inputs left unresolved by the caps
+    pub outputs: Vec<AddrTxOutput>,
+}
+
+/// Response for `/api/addr/{address}/txs` (newest first)
+#[derive(Serialize)]
+pub struct AddrTxsView {
+    pub address: String,
+    pub txs: Vec<AddrTx>,
+    pub more_txs: bool,
+
+    // paging meta
+    pub total_txs: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// Spend status of one output, for `/api/tx/{txid}/outspends`
+#[derive(Serialize, Default)]
+pub struct Outspend {
+    pub spent: bool,
+    pub txid: Option<String>,
+    pub vin: Option<u32>,
+    pub confirmed: bool,
+}
+
+/// Body of `POST /api/tx/decode`
+#[derive(Deserialize)]
+pub struct TxDecodeReq {
+    pub hex: String,
+}
+
 #[derive(Deserialize)]
 pub struct ResolveQ {
     pub resolve: Option<usize>,
+    #[serde(default, deserialize_with = "de_flag")]
+    pub verify_inputs: Option<bool>, // false = skip prevout resolution entirely
+}
+
+/// Query-string on/off value, spelled any way `config::parse_flag` accepts.
+fn de_flag<'de, D: Deserializer<'de>>(d: D) -> Result<Option<bool>, D::Error> {
+    Option::<String>::deserialize(d)?
+        .map(|v| {
+            crate::config::parse_flag(&v)
+                .ok_or_else(|| D::Error::custom(format!("expected true/false or 1/0, got {v}")))
+        })
+        .transpose()
 }
\ No newline at end of file
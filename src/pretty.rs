@@ -0,0 +1,33 @@
+use axum::{
+    body::Body,
+    extract::{Query, Request},
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+
+use crate::utils::buffer_json_body;
+
+#[derive(Deserialize)]
+struct PrettyQ {
+    pretty: Option<u8>,
+}
+
+/// `?pretty=1`: re-serialize JSON responses indented, for debugging.
+pub async fn pretty_json(req: Request, next: Next) -> Response {
+    let pretty = Query::<PrettyQ>::try_from_uri(req.uri())
+        .ok()
+        .and_then(|q| q.0.pretty)
+        .is_some_and(|p| p != 0);
+    let res = next.run(req).await;
+
+    if !pretty {
+        return res;
+    }
+    let (parts, json) = match buffer_json_body(res).await {
+        Ok(buffered) => buffered,
+        Err(res) => return res,
+    };
+    let pretty = serde_json::to_string_pretty(&json).unwrap_or_else(|_| json.to_string());
+    Response::from_parts(parts, Body::from(pretty))
+}
@@ -0,0 +1,63 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::Response,
+};
+use tower_http::request_id::RequestId;
+
+/// Largest error body we will rewrite to carry the request id.
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// Read the id assigned by `SetRequestIdLayer` (falls back to "-").
+pub fn request_id_of<B>(req: &axum::http::Request<B>) -> String {
+    req.extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .unwrap_or("-")
+        .to_string()
+}
+
+/// Appends the request id to plain-text error response bodies so users can
+/// quote it when reporting a failure.
+pub async fn tag_errors(req: Request, next: Next) -> Response {
+    let id = request_id_of(&req);
+    let res = next.run(req).await;
+
+    let status = res.status();
+    if !(status.is_client_error() || status.is_server_error()) {
+        return res;
+    }
+    // structured (e.g. JSON) error bodies keep their shape; X-Request-Id still applies
+    let is_text = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_none_or(|v| v.starts_with("text/plain"));
+    if !is_text {
+        return res;
+    }
+
+    let (mut parts, body) = res.into_parts();
+    let bytes = match to_bytes(body, MAX_ERROR_BODY).await {
+        Ok(b) => b,
+        Err(_) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    let tagged = if text.is_empty() {
+        format!("request id: {id}")
+    } else {
+        format!("{text} (request id: {id})")
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    Response::from_parts(parts, Body::from(tagged))
+}
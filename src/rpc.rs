@@ -39,21 +39,52 @@ pub async fn rpc_call<T: DeserializeOwned>(
     let res = st.http
         .post(&st.rpc_url)
         .basic_auth(&st.rpc_user, Some(&st.rpc_pass))
+        .timeout(st.rpc_timeout_for(method))
         .json(&req)
         .send()
         .await
+        .inspect_err(|e| tracing::warn!(method, error = %e, "rpc send failed"))
         .context("rpc http send failed")?;
 
     let status: HttpStatus = res.status();
-    let body = res
-        .json::<RpcResponse<T>>()
+    let text = res
+        .text()
         .await
-        .with_context(|| format!("rpc parse failed (status {status})"))?;
+        .inspect_err(|e| tracing::warn!(method, %status, error = %e, "rpc read failed"))
+        .with_context(|| format!("rpc body read failed (status {status})"))?;
+
+    let body = match serde_json::from_str::<RpcResponse<T>>(&text) {
+        Ok(body) => body,
+        Err(e) => {
+            // a proxy error page or empty body says more than the serde error
+            let err = if serde_json::from_str::<serde::de::IgnoredAny>(&text).is_ok() {
+                anyhow::anyhow!("rpc parse failed (status {status}): {e}")
+            } else {
+                anyhow::anyhow!("RPC returned non-JSON (status {status}): {}", snippet(&text))
+            };
+            tracing::warn!(method, %status, error = %err, "rpc parse failed");
+            return Err(err);
+        }
+    };
 
     if let Some(err) = body.error {
+        tracing::warn!(method, code = err.code, message = %err.message, "rpc error");
         return Err(anyhow::anyhow!("rpc error {}: {}", err.code, err.message));
     }
     body
         .result
         .ok_or_else(|| anyhow::anyhow!("rpc response missing result"))
-}
\ No newline at end of file
+}
+
+/// First ~200 chars of a response body, for error messages.
+fn snippet(text: &str) -> String {
+    const MAX: usize = 200;
+    let text = text.trim();
+    if text.is_empty() {
+        return "<empty body>".to_string();
+    }
+    match text.char_indices().nth(MAX) {
+        Some((cut, _)) => format!("{}...", &text[..cut]),
+        None => text.to_string(),
+    }
+}
@@ -1,23 +1,118 @@
 // state.rs
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context;
 use reqwest::Client;
+use tokio::sync::Semaphore;
+
+use crate::{
+    cache::Cache,
+    config::{Config, PageLimits},
+    electrum::CircuitBreaker,
+    models::{BlockTip, DifficultyView, FeeEstimates, NetworkSummary, RecommendedFees},
+    tasks::{mempool::MempoolSampler, zmq::ZmqListener},
+};
 
-#[derive(Clone)]
 pub struct AppState {
     pub http: Client,
     pub rpc_url: String,
     pub rpc_user: String,
     pub rpc_pass: String,
+    pub rpc_timeout: Duration,
+    pub rpc_method_timeouts: HashMap<String, Duration>,
     pub electrs_addr: String,
+    pub tx_default_resolve: usize,
+    pub tx_max_resolve: usize,
+    pub block_txs_page: PageLimits,
+    pub addr_txs_page: PageLimits,
+    pub addr_txs_max_resolve: usize,
+    pub expose_peers: bool,
+    pub address_labels: HashMap<String, String>,
+    pub electrum_breaker: CircuitBreaker,
+    pub electrum_slots: Arc<Semaphore>,
+    pub electrum_queue_timeout: Duration,
+    pub tip_cache: Cache<(), BlockTip>,
+    pub network_cache: Cache<(), NetworkSummary>,
+    pub difficulty_cache: Cache<(), DifficultyView>,
+    pub fees_cache: Cache<(), RecommendedFees>,
+    pub fee_table_cache: Cache<String, FeeEstimates>, // keyed by tip hash
+    pub mempool: MempoolSampler,
+    pub last_tip: Mutex<Option<String>>, // tip watcher's last seen hash
+    pub zmq: ZmqListener,
 }
 
 impl AppState {
-    pub fn new(rpc_url: String, rpc_user: String, rpc_pass: String, electrs_addr: String) -> Self {
-        Self {
+    pub fn new(cfg: &Config) -> anyhow::Result<Self> {
+        let address_labels = match &cfg.address_labels_file {
+            Some(path) => {
+                let raw = std::fs::read_to_string(path)
+                    .with_context(|| format!("reading ADDRESS_LABELS_FILE {path}"))?;
+                serde_json::from_str(&raw)
+                    .with_context(|| format!("ADDRESS_LABELS_FILE {path} must be a JSON object of address -> label"))?
+            }
+            None => HashMap::new(),
+        };
+
+        Ok(Self {
             http: Client::new(),
-            rpc_url,
-            rpc_user,
-            rpc_pass,
-            electrs_addr,
-        }
+            rpc_url: cfg.rpc_url.clone(),
+            rpc_user: cfg.rpc_user.clone(),
+            rpc_pass: cfg.rpc_pass.clone(),
+            rpc_timeout: Duration::from_secs(cfg.rpc_timeout_secs),
+            rpc_method_timeouts: cfg
+                .rpc_method_timeouts
+                .iter()
+                .map(|(m, secs)| (m.clone(), Duration::from_secs(*secs)))
+                .collect(),
+            electrs_addr: cfg.electrs_addr.clone(),
+            tx_default_resolve: cfg.tx_default_resolve,
+            tx_max_resolve: cfg.tx_max_resolve,
+            block_txs_page: cfg.block_txs_page,
+            addr_txs_page: cfg.addr_txs_page,
+            addr_txs_max_resolve: cfg.addr_txs_max_resolve,
+            expose_peers: cfg.expose_peers,
+            address_labels,
+            electrum_breaker: CircuitBreaker::new(
+                cfg.electrs_breaker_threshold,
+                Duration::from_secs(cfg.electrs_breaker_window_secs),
+                Duration::from_secs(cfg.electrs_breaker_cooldown_secs),
+            ),
+            electrum_slots: Arc::new(Semaphore::new(cfg.electrs_max_concurrency.max(1))),
+            electrum_queue_timeout: Duration::from_secs(cfg.electrs_queue_timeout_secs),
+            // short TTL to absorb bursty polling
+            tip_cache: Cache::new(Duration::from_secs(2), 1),
+            network_cache: Cache::new(Duration::from_secs(10), 1),
+            difficulty_cache: Cache::new(Duration::from_secs(10), 1),
+            fees_cache: Cache::new(Duration::from_secs(5), 1),
+            // estimates only move with new blocks; the TTL bounds mempool drift
+            fee_table_cache: Cache::new(Duration::from_secs(60), 2),
+            mempool: MempoolSampler::new(
+                Duration::from_secs(cfg.mempool_sample_secs),
+                Duration::from_secs(cfg.mempool_history_minutes * 60),
+                cfg.mempool_recent_txs,
+                Duration::from_secs(cfg.mempool_recent_secs),
+            ),
+            last_tip: Mutex::new(None),
+            zmq: ZmqListener::new(cfg.zmq_recent_txs),
+        })
+    }
+
+    /// Timeout for one RPC call: the per-method override, else the global one.
+    pub fn rpc_timeout_for(&self, method: &str) -> Duration {
+        self.rpc_method_timeouts.get(method).copied().unwrap_or(self.rpc_timeout)
+    }
+
+    /// Prevouts to resolve per transaction for a requested `?resolve=`.
+    pub fn resolve_cap(&self, requested: Option<usize>) -> usize {
+        requested.unwrap_or(self.tx_default_resolve).min(self.tx_max_resolve)
+    }
+
+    /// Known label for `addr`, if the labels file lists it.
+    pub fn label_for(&self, addr: &str) -> Option<String> {
+        self.address_labels.get(addr).cloned()
     }
 }
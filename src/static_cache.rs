@@ -0,0 +1,55 @@
+use std::sync::LazyLock;
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use regex::Regex;
+
+/// `name.3f9a1c2b.js` / `name-3f9a1c2b.css`: content-hashed, safe to cache forever.
+static HASHED_ASSET: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[.-]([0-9a-fA-F]{8,})\.[A-Za-z0-9]+$").unwrap());
+
+/// Whether `path` carries a content hash. The hex run must mix digits and
+/// letters, so dates (`report-20241017.pdf`) and words (`deadbeef`) don't count.
+fn is_hashed(path: &str) -> bool {
+    HASHED_ASSET.captures(path).is_some_and(|c| {
+        let hash = &c[1];
+        hash.bytes().any(|b| b.is_ascii_digit()) && hash.bytes().any(|b| b.is_ascii_alphabetic())
+    })
+}
+
+/// Cache-Control for `/static`: `max-age` from `STATIC_CACHE_SECS`, plus
+/// `immutable` (one year) for content-hashed file names.
+pub async fn cache_control(max_age: u64, req: Request, next: Next) -> Response {
+    let hashed = is_hashed(req.uri().path());
+    let mut res = next.run(req).await;
+
+    if res.status().is_success() {
+        let value = if hashed {
+            "public, max-age=31536000, immutable".to_string()
+        } else {
+            format!("public, max-age={max_age}")
+        };
+        if let Ok(v) = HeaderValue::from_str(&value) {
+            res.headers_mut().insert(header::CACHE_CONTROL, v);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashed_asset_names() {
+        assert!(is_hashed("/app.3f9a1c2b.js"));
+        assert!(is_hashed("/style-0a1b2c3d4e.css"));
+        assert!(!is_hashed("/report-20241017.pdf"));
+        assert!(!is_hashed("/deadbeef.deadbeef.png"));
+        assert!(!is_hashed("/app.js"));
+    }
+}
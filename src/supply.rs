@@ -1,23 +1,95 @@
+use crate::models::SupplyEra;
+
+/// Blocks between subsidy halvings.
+pub const HALVING_INTERVAL: u64 = 210_000;
+
+/// The genesis coinbase output is unspendable, so supply figures leave it out.
+const GENESIS_SUBSIDY_SATS: u64 = 50_0000_0000;
+
 /// Total mined supply up to (and including) `height` in BTC.
 /// (Genesis subsidy excluded.)
 pub fn mined_supply_btc(height: u64) -> f64 {
-    let mut remaining = height;
+    // blocks 0..=height, then drop the genesis subsidy
+    let mut remaining = height + 1;
     let mut subsidy_sats: u64 = 50_0000_0000; // 50 BTC
     let mut total_sats: u128 = 0;
 
     for _ in 0..64 {
         if remaining == 0 || subsidy_sats == 0 { break; }
-        let blocks = remaining.min(210_000);
+        let blocks = remaining.min(HALVING_INTERVAL);
         total_sats += (blocks as u128) * (subsidy_sats as u128);
         remaining -= blocks;
         subsidy_sats >>= 1;
     }
-    (total_sats as f64) / 100_000_000.0
+    ((total_sats - GENESIS_SUBSIDY_SATS as u128) as f64) / 100_000_000.0
 }
 
 /// Current block subsidy in BTC at `height`.
 pub fn current_subsidy_btc(height: u64) -> f64 {
-    let halvings = (height / 210_000) as u32;
+    let halvings = (height / HALVING_INTERVAL) as u32;
     let sats: u64 = if halvings >= 64 { 0 } else { 50_0000_0000 >> halvings };
     (sats as f64) / 100_000_000.0
 }
+
+/// Per-era breakdown of the issuance `mined_supply_btc` sums up, ending with
+/// the last era that still pays a non-zero subsidy. Like `mined_supply_btc`,
+/// era 0 leaves out the genesis subsidy.
+pub fn halving_schedule() -> Vec<SupplyEra> {
+    let mut eras = Vec::new();
+    let mut subsidy_sats: u64 = 50_0000_0000;
+    let mut total_sats: u128 = 0;
+
+    for era in 0..64u64 {
+        if subsidy_sats == 0 { break; }
+        let mut issued_sats = (HALVING_INTERVAL as u128) * (subsidy_sats as u128);
+        if era == 0 {
+            issued_sats -= GENESIS_SUBSIDY_SATS as u128;
+        }
+        total_sats += issued_sats;
+        eras.push(SupplyEra {
+            era,
+            start_height: era * HALVING_INTERVAL,
+            subsidy_btc: (subsidy_sats as f64) / 100_000_000.0,
+            issued_btc: (issued_sats as f64) / 100_000_000.0,
+            cumulative_btc: (total_sats as f64) / 100_000_000.0,
+        });
+        subsidy_sats >>= 1;
+    }
+    eras
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsidy_at_halving_boundaries() {
+        assert_eq!(current_subsidy_btc(0), 50.0);
+        assert_eq!(current_subsidy_btc(209_999), 50.0);
+        assert_eq!(current_subsidy_btc(210_000), 25.0);
+        assert_eq!(current_subsidy_btc(630_000), 6.25);
+        assert_eq!(current_subsidy_btc(64 * 210_000), 0.0);
+    }
+
+    #[test]
+    fn mined_supply_at_first_halving() {
+        assert_eq!(mined_supply_btc(0), 0.0);
+        assert_eq!(mined_supply_btc(1), 50.0);
+        assert_eq!(mined_supply_btc(209_999), 10_499_950.0);
+        assert_eq!(mined_supply_btc(210_000), 10_499_975.0);
+        assert_eq!(mined_supply_btc(210_001), 10_500_000.0);
+    }
+
+    #[test]
+    fn schedule_matches_mined_supply() {
+        let eras = halving_schedule();
+        assert_eq!(eras.len(), 33);
+        assert_eq!(eras[1].start_height, 210_000);
+        assert_eq!(eras[1].subsidy_btc, 25.0);
+        for e in &eras {
+            let end = e.start_height + HALVING_INTERVAL - 1;
+            assert_eq!(e.cumulative_btc, mined_supply_btc(end));
+        }
+        assert_eq!(eras[0].issued_btc, 10_499_950.0);
+    }
+}
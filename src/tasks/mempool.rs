@@ -0,0 +1,212 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use tokio::time::MissedTickBehavior;
+
+use crate::{
+    electrum,
+    handlers::mempool::MAX_VERBOSE_TXS,
+    models::{MempoolEntry, MempoolInfo, MempoolPoint, RecentMempoolTx},
+    rpc::rpc_call,
+    state::AppState,
+    utils::unix_now,
+};
+
+/// One periodic view of the mempool, shared by the live mempool endpoints.
+pub struct MempoolSnapshot {
+    pub taken_at: Instant,
+    pub info: MempoolInfo,
+    pub histogram: Option<Vec<(f64, u64)>>, // None if Electrum was unavailable
+    pub recent: Vec<RecentMempoolTx>,        // newest first
+    recent_at: Option<Instant>,              // when `recent` was last refreshed
+}
+
+/// Latest mempool snapshot plus a ring buffer of compact history points,
+/// refreshed by `run` every `interval`.
+pub struct MempoolSampler {
+    interval: Duration,
+    capacity: usize,
+    recent_txs: usize,
+    recent_every: Duration,
+    latest: RwLock<Option<Arc<MempoolSnapshot>>>,
+    history: RwLock<VecDeque<MempoolPoint>>,
+}
+
+impl MempoolSampler {
+    pub fn new(interval: Duration, history: Duration, recent_txs: usize, recent_every: Duration) -> Self {
+        let interval = interval.max(Duration::from_secs(1));
+        let capacity = (history.as_secs() / interval.as_secs()).max(1) as usize;
+        Self {
+            interval,
+            capacity,
+            recent_txs,
+            recent_every,
+            latest: RwLock::new(None),
+            history: RwLock::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Latest snapshot, unless the sampler has fallen more than two
+    /// intervals behind; callers then query Core themselves.
+    pub fn latest(&self) -> Option<Arc<MempoolSnapshot>> {
+        self.latest
+            .read()
+            .unwrap()
+            .clone()
+            .filter(|s| s.taken_at.elapsed() <= self.interval * 2)
+    }
+
+    /// History points from the last `minutes`, oldest first.
+    pub fn history(&self, minutes: u64) -> Vec<MempoolPoint> {
+        let since = unix_now().saturating_sub(minutes.saturating_mul(60));
+        self.history
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|p| p.time >= since)
+            .cloned()
+            .collect()
+    }
+
+    fn record(&self, snap: MempoolSnapshot) {
+        let point = MempoolPoint {
+            time: unix_now(),
+            count: snap.info.size,
+            vbytes: snap.info.bytes,
+            min_fee_sat_vb: snap.info.mempoolminfee * 100_000_000.0 / 1000.0,
+        };
+        let mut history = self.history.write().unwrap();
+        if history.len() == self.capacity {
+            history.pop_front();
+        }
+        history.push_back(point);
+        drop(history);
+
+        *self.latest.write().unwrap() = Some(Arc::new(snap));
+    }
+}
+
+/// Sample forever; failures are logged and the previous snapshot ages out.
+pub async fn run(st: Arc<AppState>) {
+    let mut tick = tokio::time::interval(st.mempool.interval);
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        match sample(&st).await {
+            Ok(snap) => st.mempool.record(snap),
+            Err(e) => tracing::warn!(error = %e, "mempool sample failed"),
+        }
+    }
+}
+
+async fn sample(st: &Arc<AppState>) -> anyhow::Result<MempoolSnapshot> {
+    let info: MempoolInfo = rpc_call(st, "getmempoolinfo", serde_json::json!([])).await?;
+
+    let histogram = match fee_histogram(st).await {
+        Ok(h) => Some(h),
+        Err(e) => {
+            tracing::warn!(error = %e, "mempool sample: electrum fee histogram failed");
+            None
+        }
+    };
+
+    // The verbose dump is the only source of entry times and is heavy, so the
+    // newest entries are refreshed every `recent_every` rather than every tick,
+    // and a failed refresh keeps the previous list.
+    let prev = st.mempool.latest.read().unwrap().clone();
+    let due = prev
+        .as_ref()
+        .and_then(|p| p.recent_at)
+        .is_none_or(|t| t.elapsed() >= st.mempool.recent_every);
+    let (recent, recent_at) = match (due, prev) {
+        (false, Some(prev)) => (prev.recent.clone(), prev.recent_at),
+        (_, prev) => match newest_entries(st, &info).await {
+            Ok(recent) => (recent, Some(Instant::now())),
+            Err(e) => {
+                tracing::warn!(error = %e, "mempool sample: recent entries failed");
+                prev.map_or((Vec::new(), None), |p| (p.recent.clone(), p.recent_at))
+            }
+        },
+    };
+
+    Ok(MempoolSnapshot { taken_at: Instant::now(), info, histogram, recent, recent_at })
+}
+
+/// Newest entries from `getrawmempool true`; empty when the mempool is too
+/// large for the verbose dump.
+async fn newest_entries(st: &AppState, info: &MempoolInfo) -> anyhow::Result<Vec<RecentMempoolTx>> {
+    if info.size > MAX_VERBOSE_TXS {
+        return Ok(Vec::new());
+    }
+    let entries: HashMap<String, MempoolEntry> =
+        rpc_call(st, "getrawmempool", serde_json::json!([true])).await?;
+    Ok(newest(entries, st.mempool.recent_txs))
+}
+
+async fn fee_histogram(st: &Arc<AppState>) -> anyhow::Result<Vec<(f64, u64)>> {
+    let permit = electrum::permit(st).await.map_err(|(_, msg)| anyhow::anyhow!(msg))?;
+    let st_blocking = st.clone();
+    tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        electrum::connect(&st_blocking)?.fee_histogram()
+    })
+    .await?
+}
+
+fn newest(entries: HashMap<String, MempoolEntry>, n: usize) -> Vec<RecentMempoolTx> {
+    let mut recent: Vec<RecentMempoolTx> = entries
+        .into_iter()
+        .map(|(txid, e)| RecentMempoolTx {
+            txid,
+            time: e.time,
+            vsize: Some(e.vsize),
+            fee_sats: e.fees.map(|f| (f.base * 100_000_000.0).round() as u64),
+        })
+        .collect();
+    recent.sort_by(|a, b| b.time.cmp(&a.time).then_with(|| a.txid.cmp(&b.txid)));
+    recent.truncate(n);
+    recent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(size: u64) -> MempoolSnapshot {
+        MempoolSnapshot {
+            taken_at: Instant::now(),
+            info: MempoolInfo {
+                size,
+                bytes: size * 200,
+                usage: 0,
+                fullrbf: false,
+                unbroadcastcount: 0,
+                mempoolminfee: 0.00001,
+            },
+            histogram: None,
+            recent: Vec::new(),
+            recent_at: None,
+        }
+    }
+
+    #[test]
+    fn history_is_a_bounded_ring() {
+        let sampler = MempoolSampler::new(
+            Duration::from_secs(30),
+            Duration::from_secs(90),
+            10,
+            Duration::from_secs(300),
+        );
+        for size in 1..=5 {
+            sampler.record(snapshot(size));
+        }
+        let counts: Vec<u64> = sampler.history(60).iter().map(|p| p.count).collect();
+        assert_eq!(counts, vec![3, 4, 5]);
+        assert!((sampler.history(60)[0].min_fee_sat_vb - 1.0).abs() < 1e-9);
+        assert_eq!(sampler.latest().unwrap().info.size, 5);
+        assert_eq!(sampler.history(u64::MAX).len(), 3);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod mempool;
+pub mod tip;
+pub mod zmq;
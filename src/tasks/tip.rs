@@ -0,0 +1,37 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::time::MissedTickBehavior;
+
+use crate::{rpc::rpc_call, state::AppState};
+
+/// Record `hash` as the chain tip. On a change, clear the tip-derived caches
+/// so they stop serving the old tip until their TTL runs out. The per-tip fee
+/// table is keyed by hash and needs no clearing.
+pub fn observe(st: &AppState, hash: String) {
+    let mut last = st.last_tip.lock().unwrap();
+    if last.as_ref().is_some_and(|prev| *prev != hash) {
+        tracing::info!(%hash, "new tip, clearing tip caches");
+        st.tip_cache.clear();
+        st.network_cache.clear();
+        st.difficulty_cache.clear();
+        st.fees_cache.clear();
+    }
+    *last = Some(hash);
+}
+
+/// Poll `getbestblockhash` every `every`: the tip watcher's source when no
+/// ZMQ `hashblock` feed is configured, or while that feed is disconnected.
+pub async fn run(st: Arc<AppState>, every: Duration) {
+    let mut tick = tokio::time::interval(every);
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        if st.zmq.hashblock_connected() {
+            continue;
+        }
+        match rpc_call::<String>(&st, "getbestblockhash", serde_json::json!([])).await {
+            Ok(hash) => observe(&st, hash),
+            Err(e) => tracing::warn!(error = %e, "tip watcher: getbestblockhash failed"),
+        }
+    }
+}
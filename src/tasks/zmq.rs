@@ -0,0 +1,147 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    models::{RecentMempoolTx, ZmqSocketStatus},
+    state::AppState,
+    tasks::tip,
+    utils::unix_now,
+    zmtp::Subscriber,
+};
+
+/// Reconnect delays double from the first to the last, then stay there.
+const BACKOFF_MIN: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Connection state of the bitcoind ZMQ subscriptions plus the ring buffer
+/// of txids announced on `hashtx`.
+pub struct ZmqListener {
+    sockets: Mutex<Vec<ZmqSocketStatus>>,
+    recent: Mutex<VecDeque<RecentMempoolTx>>, // newest first
+    recent_cap: usize,
+}
+
+impl ZmqListener {
+    pub fn new(recent_cap: usize) -> Self {
+        Self {
+            sockets: Mutex::new(Vec::new()),
+            recent: Mutex::new(VecDeque::with_capacity(recent_cap)),
+            recent_cap: recent_cap.max(1),
+        }
+    }
+
+    /// One entry per configured subscription, for `/health/ready`.
+    pub fn status(&self) -> Vec<ZmqSocketStatus> {
+        self.sockets.lock().unwrap().clone()
+    }
+
+    /// Whether `hashblock` notifications are currently arriving, in which
+    /// case the tip poller can stand down.
+    pub fn hashblock_connected(&self) -> bool {
+        self.sockets.lock().unwrap().iter().any(|s| s.topic == "hashblock" && s.connected)
+    }
+
+    /// Newest txids seen on `hashtx`, or None when that feed is not configured
+    /// or has not delivered anything yet. bitcoind also announces every tx of
+    /// a newly connected block here, so just after a block some entries may
+    /// already be confirmed.
+    pub fn recent(&self) -> Option<Vec<RecentMempoolTx>> {
+        let recent = self.recent.lock().unwrap();
+        (!recent.is_empty()).then(|| recent.iter().cloned().collect())
+    }
+
+    fn push_recent(&self, txid: String) {
+        let mut recent = self.recent.lock().unwrap();
+        // the same tx comes around again when its block connects; keep the first sighting
+        if recent.iter().any(|t| t.txid == txid) {
+            return;
+        }
+        if recent.len() == self.recent_cap {
+            recent.pop_back();
+        }
+        recent.push_front(RecentMempoolTx { txid, time: unix_now(), vsize: None, fee_sats: None });
+    }
+
+    fn update(&self, topic: &str, f: impl FnOnce(&mut ZmqSocketStatus)) {
+        if let Some(s) = self.sockets.lock().unwrap().iter_mut().find(|s| s.topic == topic) {
+            f(s);
+        }
+    }
+}
+
+/// Subscribe to `topic` (`hashblock` or `hashtx`) at `addr` for the life of
+/// the process, reconnecting with backoff whenever bitcoind goes away.
+pub async fn run(st: Arc<AppState>, topic: &'static str, addr: String) {
+    st.zmq.sockets.lock().unwrap().push(ZmqSocketStatus {
+        topic,
+        addr: addr.clone(),
+        connected: false,
+        reconnects: 0,
+        last_message_at: None,
+        last_error: None,
+    });
+
+    let mut backoff = BACKOFF_MIN;
+    loop {
+        let err = match Subscriber::connect(&addr, topic.as_bytes()).await {
+            Ok(mut sub) => {
+                tracing::info!(topic, %addr, "zmq subscribed");
+                st.zmq.update(topic, |s| s.connected = true);
+                backoff = BACKOFF_MIN;
+                loop {
+                    match sub.recv().await {
+                        Ok(parts) => handle(&st, topic, &parts),
+                        Err(e) => break e,
+                    }
+                }
+            }
+            Err(e) => e,
+        };
+
+        let err = format!("{err:#}");
+        tracing::warn!(topic, %addr, error = %err, retry_in = ?backoff, "zmq subscription lost");
+        st.zmq.update(topic, |s| {
+            s.connected = false;
+            s.reconnects += 1;
+            s.last_error = Some(err);
+        });
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(BACKOFF_MAX);
+    }
+}
+
+/// bitcoind sends `[topic, 32-byte hash in display order, 4-byte sequence]`.
+fn handle(st: &AppState, topic: &str, parts: &[Vec<u8>]) {
+    let [name, hash, ..] = parts else { return };
+    if name.as_slice() != topic.as_bytes() || hash.len() != 32 {
+        return;
+    }
+    let hash = hex::encode(hash);
+    st.zmq.update(topic, |s| s.last_message_at = Some(unix_now()));
+
+    match topic {
+        "hashblock" => tip::observe(st, hash),
+        "hashtx" => st.zmq.push_recent(hash),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_ring_is_newest_first_and_deduplicated() {
+        let z = ZmqListener::new(2);
+        assert!(z.recent().is_none());
+        z.push_recent("a".to_string());
+        z.push_recent("b".to_string());
+        z.push_recent("a".to_string());
+        z.push_recent("c".to_string());
+        let txids: Vec<String> = z.recent().unwrap().into_iter().map(|t| t.txid).collect();
+        assert_eq!(txids, ["c", "b"]);
+    }
+}
@@ -1,4 +1,8 @@
-use axum::http::StatusCode;
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    http::{header, response::Parts, StatusCode},
+    response::Response,
+};
 
 use crate::models::TxDecoded;
 
@@ -10,7 +14,216 @@ pub fn vout_value_btc(v: &serde_json::Value) -> f64 {
     v.get("value").and_then(|x| x.as_f64()).unwrap_or(0.0)
 }
 
+/// Block fullness relative to the 4,000,000 WU consensus limit.
+pub fn weight_percent(weight: Option<u64>) -> Option<f64> {
+    weight.map(|w| w as f64 / 4_000_000.0 * 100.0)
+}
+
 pub fn tx_is_coinbase(tx: &TxDecoded) -> bool {
     if tx.vin.is_empty() { return false; }
     tx.vin[0].get("coinbase").is_some()
 }
+
+/// First mainnet block whose coinbase must push its height (BIP34).
+pub const BIP34_HEIGHT: u64 = 227_931;
+
+/// Height pushed at the start of a coinbase scriptSig (BIP34). Only trusted
+/// when it matches `block_height`: older coinbases often lead with nBits or
+/// an extranonce, which would otherwise read as a bogus height.
+pub fn bip34_height(script_sig: &[u8], block_height: u64) -> Option<u64> {
+    if block_height < BIP34_HEIGHT {
+        return None;
+    }
+    let (&len, rest) = script_sig.split_first()?;
+    let pushed = match len {
+        0x51..=0x60 => Some((len - 0x50) as u64), // OP_1..OP_16
+        1..=8 if rest.len() >= len as usize => Some(
+            rest[..len as usize]
+                .iter()
+                .rev()
+                .fold(0u64, |acc, b| (acc << 8) | *b as u64),
+        ),
+        _ => None,
+    }?;
+    (pushed == block_height).then_some(pushed)
+}
+
+/// Printable ASCII runs (4+ chars) in a coinbase scriptSig, e.g. pool tags.
+pub fn coinbase_tag(script_sig: &[u8]) -> Option<String> {
+    let parts: Vec<String> = script_sig
+        .split(|b| !(0x20..0x7f).contains(b))
+        .filter(|run| run.len() >= 4)
+        .map(|run| String::from_utf8_lossy(run).trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if parts.is_empty() { None } else { Some(parts.join(" ")) }
+}
+
+/// Core's default dust threshold (`-dustrelayfee` = 3 sat/vB) for an output with
+/// the given `scriptPubKey.type` and script length: the cost of creating the
+/// output plus later spending it. `None` for unspendable OP_RETURN outputs.
+pub fn dust_limit_sats(script_type: &str, script_len: usize) -> Option<u64> {
+    if script_type == "nulldata" {
+        return None;
+    }
+    let len_prefix = if script_len < 0xfd { 1 } else { 3 };
+    let output_size = 8 + len_prefix + script_len;
+    let is_witness = script_type.starts_with("witness_") || script_type == "anchor";
+    // outpoint + sequence + scriptSig, with witness data discounted 4x
+    let spend_size = if is_witness { 32 + 4 + 1 + 107 / 4 + 4 } else { 32 + 4 + 1 + 107 + 4 };
+    Some(((output_size + spend_size) * 3) as u64)
+}
+
+/// Whether a decoded vout pays less than its dust threshold.
+pub fn vout_is_dust(v: &serde_json::Value) -> bool {
+    let script_type = v.pointer("/scriptPubKey/type").and_then(|t| t.as_str()).unwrap_or("");
+    let script_len = v
+        .pointer("/scriptPubKey/hex")
+        .and_then(|h| h.as_str())
+        .map(|h| h.len() / 2)
+        .unwrap_or(0);
+    let value_sats = (vout_value_btc(v) * 100_000_000.0).round() as u64;
+    dust_limit_sats(script_type, script_len).is_some_and(|limit| value_sats < limit)
+}
+
+/// Parse a decimal BTC string ("0.0001") into sats without going through f64.
+pub fn parse_btc_sats(s: &str) -> Option<u64> {
+    let (whole, frac) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+    if frac.len() > 8 || (whole.is_empty() && frac.is_empty()) {
+        return None;
+    }
+    let digits = |d: &str| d.is_empty() || d.bytes().all(|b| b.is_ascii_digit());
+    if !digits(whole) || !digits(frac) {
+        return None;
+    }
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+    let frac: u64 = format!("{frac:0<8}").parse().ok()?;
+    whole.checked_mul(100_000_000)?.checked_add(frac)
+}
+
+/// Format sats as an exact 8-decimal BTC string.
+pub fn format_sats_btc(sats: u64) -> String {
+    format!("{}.{:08}", sats / 100_000_000, sats % 100_000_000)
+}
+
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Largest JSON body a response-rewriting middleware will buffer.
+const MAX_JSON_BODY: usize = 16 * 1024 * 1024;
+
+/// Buffer a JSON response so a middleware can rewrite it; the returned parts
+/// have CONTENT_LENGTH dropped, ready for the new body. `Err` carries the
+/// response to send instead: the original when it is not JSON, too large (or
+/// of unknown size) to buffer, or unparsable, and an empty 500 when reading
+/// the body failed part-way.
+pub async fn buffer_json_body(res: Response) -> Result<(Parts, serde_json::Value), Response> {
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !is_json || res.body().size_hint().upper().is_none_or(|n| n > MAX_JSON_BODY as u64) {
+        return Err(res);
+    }
+
+    let (mut parts, body) = res.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_JSON_BODY).await else {
+        parts.headers.remove(header::CONTENT_LENGTH);
+        parts.status = StatusCode::INTERNAL_SERVER_ERROR;
+        return Err(Response::from_parts(parts, Body::empty()));
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(json) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Ok((parts, json))
+        }
+        Err(_) => Err(Response::from_parts(parts, Body::from(bytes))),
+    }
+}
+
+/// Heights above this are rejected up front; Core takes block heights as i32.
+pub const MAX_HEIGHT: u64 = i32::MAX as u64;
+
+/// 400 unless `s` is 64 lowercase hex chars (a txid or block hash).
+pub fn validate_hash(kind: &str, s: &str) -> Result<(), (StatusCode, String)> {
+    let ok = s.len() == 64 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+    if ok {
+        Ok(())
+    } else {
+        Err((StatusCode::BAD_REQUEST, format!("invalid {kind} {s}: expected 64 lowercase hex chars")))
+    }
+}
+
+/// 400 for heights no node could have.
+pub fn validate_height(height: u64) -> Result<(), (StatusCode, String)> {
+    if height <= MAX_HEIGHT {
+        Ok(())
+    } else {
+        Err((StatusCode::BAD_REQUEST, format!("invalid height {height}: must be at most {MAX_HEIGHT}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn btc_sats_round_trip() {
+        assert_eq!(parse_btc_sats("1"), Some(100_000_000));
+        assert_eq!(parse_btc_sats("0.00000001"), Some(1));
+        assert_eq!(parse_btc_sats(".5"), Some(50_000_000));
+        assert_eq!(parse_btc_sats("0.000000001"), None);
+        assert_eq!(parse_btc_sats("-1"), None);
+        assert_eq!(format_sats_btc(123_456_789), "1.23456789");
+        assert_eq!(format_sats_btc(1), "0.00000001");
+    }
+
+    #[test]
+    fn path_param_validation() {
+        let h = "00000000000000000000aa44ff9d4c07bd9b1c4a3d0b2a1e9f2c1b0a98765432";
+        assert!(validate_hash("txid", h).is_ok());
+        assert!(validate_hash("txid", &h.to_uppercase()).is_err());
+        assert!(validate_hash("txid", &h[1..]).is_err());
+        assert!(validate_hash("txid", &format!("{}g", &h[1..])).is_err());
+        assert!(validate_height(840_000).is_ok());
+        assert!(validate_height(MAX_HEIGHT + 1).is_err());
+    }
+
+    #[test]
+    fn bip34_height_only_from_bip34_blocks() {
+        // block 227,931: push of 0x037a5b
+        assert_eq!(bip34_height(&[0x03, 0x5b, 0x7a, 0x03, 0xff], 227_931), Some(227_931));
+        assert_eq!(bip34_height(&[0x03, 0x5b, 0x7a, 0x03], 227_932), None);
+        // block 1: the leading push is nBits (0x1d00ffff), not a height
+        assert_eq!(bip34_height(&[0x04, 0xff, 0xff, 0x00, 0x1d, 0x01, 0x04], 1), None);
+    }
+
+    #[test]
+    fn dust_limits_by_script_type() {
+        assert_eq!(dust_limit_sats("pubkeyhash", 25), Some(546));
+        assert_eq!(dust_limit_sats("scripthash", 23), Some(540));
+        assert_eq!(dust_limit_sats("witness_v0_keyhash", 22), Some(294));
+        assert_eq!(dust_limit_sats("witness_v0_scripthash", 34), Some(330));
+        assert_eq!(dust_limit_sats("witness_v1_taproot", 34), Some(330));
+        assert_eq!(dust_limit_sats("nulldata", 40), None);
+    }
+
+    #[test]
+    fn vout_dust_flag() {
+        let v = serde_json::json!({
+            "value": 0.00000330,
+            "scriptPubKey": { "type": "witness_v1_taproot", "hex": "51".repeat(34) }
+        });
+        assert!(!vout_is_dust(&v));
+        let v = serde_json::json!({
+            "value": 0.00000545,
+            "scriptPubKey": { "type": "pubkeyhash", "hex": "76".repeat(25) }
+        });
+        assert!(vout_is_dust(&v));
+    }
+}
@@ -0,0 +1,225 @@
+//! Just enough ZMTP 3.0 to act as a SUB socket against bitcoind's
+//! `-zmqpub*` endpoints: NULL security, one subscription, multipart reads.
+//! Avoids linking libzmq for a handful of tiny notification frames.
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use socket2::{SockRef, TcpKeepalive};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+const MORE: u8 = 0x01;
+const LONG: u8 = 0x02;
+const COMMAND: u8 = 0x04;
+
+/// Notifications are a topic, a 32-byte hash and a sequence number; anything
+/// near this size means we are not talking to what we think we are.
+const MAX_FRAME: u64 = 1024 * 1024;
+
+/// Blocks can be an hour apart, so a silent connection is normal; keepalive
+/// probes are what notice a peer that vanished without closing it.
+const KEEPALIVE_IDLE: Duration = Duration::from_secs(60);
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct Subscriber {
+    stream: BufReader<TcpStream>,
+}
+
+impl Subscriber {
+    /// Connect to `addr` (`tcp://host:port` as in bitcoind's config, or plain
+    /// `host:port`), complete the handshake and subscribe to `topic`.
+    pub async fn connect(addr: &str, topic: &[u8]) -> Result<Self> {
+        let host = addr.strip_prefix("tcp://").unwrap_or(addr);
+        let mut stream = TcpStream::connect(host)
+            .await
+            .with_context(|| format!("connecting to {addr}"))?;
+        SockRef::from(&stream).set_tcp_keepalive(
+            &TcpKeepalive::new().with_time(KEEPALIVE_IDLE).with_interval(KEEPALIVE_INTERVAL),
+        )?;
+
+        stream.write_all(&greeting()).await?;
+        let mut peer = [0u8; 64];
+        stream.read_exact(&mut peer).await.context("reading ZMTP greeting")?;
+        check_greeting(&peer)?;
+
+        let mut sub = Self { stream: BufReader::new(stream) };
+        sub.write_frame(COMMAND, &ready_command(b"SUB")).await?;
+        sub.expect_ready().await?;
+
+        // ZMTP 3.0 subscriptions are plain messages: 0x01 followed by the prefix
+        let mut subscribe = Vec::with_capacity(1 + topic.len());
+        subscribe.push(1);
+        subscribe.extend_from_slice(topic);
+        sub.write_frame(0, &subscribe).await?;
+        Ok(sub)
+    }
+
+    /// Next multipart message. Commands (e.g. heartbeats) are skipped.
+    pub async fn recv(&mut self) -> Result<Vec<Vec<u8>>> {
+        let mut parts = Vec::new();
+        loop {
+            let (flags, body) = self.read_frame().await?;
+            if flags & COMMAND != 0 {
+                continue;
+            }
+            parts.push(body);
+            if flags & MORE == 0 {
+                return Ok(parts);
+            }
+        }
+    }
+
+    async fn expect_ready(&mut self) -> Result<()> {
+        loop {
+            let (flags, body) = self.read_frame().await?;
+            if flags & COMMAND == 0 {
+                bail!("ZMTP peer sent a message before READY");
+            }
+            let name_len = *body.first().context("empty ZMTP command")? as usize;
+            match body.get(1..1 + name_len) {
+                Some(b"READY") => return Ok(()),
+                Some(b"ERROR") => {
+                    let reason = body.get(2 + name_len..).unwrap_or_default();
+                    bail!("ZMTP peer refused: {}", String::from_utf8_lossy(reason));
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    async fn read_frame(&mut self) -> Result<(u8, Vec<u8>)> {
+        let flags = self.stream.read_u8().await?;
+        let len = if flags & LONG != 0 {
+            self.stream.read_u64().await?
+        } else {
+            self.stream.read_u8().await? as u64
+        };
+        if len > MAX_FRAME {
+            bail!("ZMTP frame of {len} bytes exceeds {MAX_FRAME}");
+        }
+        let mut body = vec![0u8; len as usize];
+        self.stream.read_exact(&mut body).await?;
+        Ok((flags, body))
+    }
+
+    async fn write_frame(&mut self, flags: u8, body: &[u8]) -> Result<()> {
+        let mut frame = Vec::with_capacity(9 + body.len());
+        match u8::try_from(body.len()) {
+            Ok(len) => frame.extend_from_slice(&[flags, len]),
+            Err(_) => {
+                frame.push(flags | LONG);
+                frame.extend_from_slice(&(body.len() as u64).to_be_bytes());
+            }
+        }
+        frame.extend_from_slice(body);
+        self.stream.write_all(&frame).await?;
+        Ok(())
+    }
+}
+
+/// Signature, version 3.0, NULL mechanism, as-client.
+fn greeting() -> [u8; 64] {
+    let mut g = [0u8; 64];
+    g[0] = 0xff;
+    g[9] = 0x7f;
+    g[10] = 3;
+    g[12..16].copy_from_slice(b"NULL");
+    g
+}
+
+fn check_greeting(g: &[u8; 64]) -> Result<()> {
+    if g[0] != 0xff || g[9] & 0x01 == 0 {
+        bail!("not a ZMTP peer");
+    }
+    if g[10] < 3 {
+        bail!("ZMTP peer speaks version {}.{}, need 3.x", g[10], g[11]);
+    }
+    if &g[12..17] != b"NULL\0" {
+        bail!("ZMTP peer wants a security mechanism other than NULL");
+    }
+    Ok(())
+}
+
+/// READY command body carrying a single Socket-Type property.
+fn ready_command(socket_type: &[u8]) -> Vec<u8> {
+    let mut body = vec![5];
+    body.extend_from_slice(b"READY");
+    body.push(11);
+    body.extend_from_slice(b"Socket-Type");
+    body.extend_from_slice(&(socket_type.len() as u32).to_be_bytes());
+    body.extend_from_slice(socket_type);
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{io::AsyncRead, net::TcpListener};
+
+    async fn read_frame(s: &mut (impl AsyncRead + Unpin)) -> (u8, Vec<u8>) {
+        let flags = s.read_u8().await.unwrap();
+        let len = if flags & LONG != 0 { s.read_u64().await.unwrap() } else { s.read_u8().await.unwrap() as u64 };
+        let mut body = vec![0u8; len as usize];
+        s.read_exact(&mut body).await.unwrap();
+        (flags, body)
+    }
+
+    /// Plays bitcoind's PUB side: handshake, check the subscription, publish
+    /// one notification with a heartbeat command ahead of it.
+    #[tokio::test]
+    async fn subscribes_and_reads_a_notification() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("tcp://{}", listener.local_addr().unwrap());
+
+        let publisher = tokio::spawn(async move {
+            let (mut s, _) = listener.accept().await.unwrap();
+            let mut g = greeting();
+            g[11] = 1; // libzmq answers as 3.1
+            s.write_all(&g).await.unwrap();
+            let mut client = [0u8; 64];
+            s.read_exact(&mut client).await.unwrap();
+            assert_eq!(client, greeting());
+
+            let (flags, body) = read_frame(&mut s).await;
+            assert_eq!((flags, body), (COMMAND, ready_command(b"SUB")));
+            let ready = ready_command(b"PUB");
+            s.write_all(&[COMMAND, ready.len() as u8]).await.unwrap();
+            s.write_all(&ready).await.unwrap();
+
+            let (flags, body) = read_frame(&mut s).await;
+            assert_eq!((flags, body), (0, b"\x01hashblock".to_vec()));
+
+            s.write_all(&[COMMAND, 5, 4]).await.unwrap();
+            s.write_all(b"PING").await.unwrap();
+            s.write_all(&[MORE, 9]).await.unwrap();
+            s.write_all(b"hashblock").await.unwrap();
+            s.write_all(&[MORE, 32]).await.unwrap();
+            s.write_all(&[0xab; 32]).await.unwrap();
+            s.write_all(&[0, 4, 7, 0, 0, 0]).await.unwrap();
+        });
+
+        let mut sub = Subscriber::connect(&addr, b"hashblock").await.unwrap();
+        let parts = sub.recv().await.unwrap();
+        assert_eq!(parts, vec![b"hashblock".to_vec(), vec![0xab; 32], vec![7, 0, 0, 0]]);
+        publisher.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_non_null_mechanism() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut s, _) = listener.accept().await.unwrap();
+            let mut g = greeting();
+            g[12..17].copy_from_slice(b"PLAIN");
+            s.write_all(&g).await.unwrap();
+            let mut client = [0u8; 64];
+            let _ = s.read_exact(&mut client).await;
+        });
+        let err = Subscriber::connect(&addr, b"hashtx").await.err().unwrap();
+        assert!(err.to_string().contains("NULL"), "{err}");
+    }
+}